@@ -1,10 +1,153 @@
 use thiserror::Error;
 
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum NetworkError {
     #[error("Failed to make request: {0}")]
     ReqwestError(#[from] reqwest::Error),
 
     #[error("Status code: {0}")]
     StatusCodeError(reqwest::StatusCode),
+
+    #[error("Failed to deserialize response: {0}")]
+    DeserializeError(#[from] serde_json::Error),
+
+    #[error("Invalid header value: {0}")]
+    InvalidHeaderError(#[from] reqwest::header::InvalidHeaderValue),
+
+    #[error("Missing data in the response")]
+    MissingData,
+}
+
+#[derive(Error, Debug)]
+pub enum LicenseKeyInstancesError {
+    #[error(transparent)]
+    NetworkError(#[from] NetworkError),
+
+    #[error(transparent)]
+    ValidationError(#[from] ValidationError),
+}
+
+#[derive(Error, Debug)]
+pub enum LicenseTokenError {
+    #[error("License key is not valid: {0}")]
+    InvalidLicense(String),
+
+    #[error("License key is {0}")]
+    InactiveLicense(crate::license_keys::LicenseKeyStatus),
+
+    #[error("License key expired")]
+    LicenseExpired,
+
+    #[error("Malformed license token")]
+    Malformed,
+
+    #[error("License token signature does not match")]
+    InvalidSignature,
+
+    #[error("License token expired")]
+    Expired,
+
+    #[error("Failed to serialize license token: {0}")]
+    SerializationError(#[from] serde_json::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum ProrationError {
+    #[error("Invalid renewal date: {0}")]
+    InvalidRenewalDate(String),
+
+    #[error("Price {0} is not a subscription price")]
+    NotRecurring(i64),
+
+    #[error("Unsupported renewal interval: {0}")]
+    UnsupportedInterval(String),
+
+    #[error("Renewal interval out of range: {0} {1}")]
+    IntervalOutOfRange(i64, String),
+
+    #[error("Price {0} is usage-based, its proration can't be estimated")]
+    UsageBased(i64),
+
+    #[error(transparent)]
+    PricingError(#[from] PricingError),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PricingError {
+    #[error("Unknown pricing scheme: {0}")]
+    UnknownScheme(String),
+
+    #[error("The {0} pricing scheme requires tiers")]
+    MissingTiers(String),
+
+    #[error("Invalid tier: {0}")]
+    InvalidTier(String),
+
+    #[error("Invalid package size: {0:?}")]
+    InvalidPackageSize(Option<i64>),
+
+    #[error("Quantity can't be negative: {0}")]
+    NegativeQuantity(i64),
+
+    #[error("Quantity {0} is above the last tier")]
+    QuantityAboveTiers(i64),
+
+    #[error("Amount overflows")]
+    Overflow,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum MoneyError {
+    #[error("Currencies don't match: {0} and {1}")]
+    CurrencyMismatch(String, String),
+
+    #[error("Amount overflows")]
+    Overflow,
+}
+
+#[derive(Error, Debug)]
+pub enum UsageProjectionError {
+    #[error(transparent)]
+    NetworkError(#[from] NetworkError),
+
+    #[error(transparent)]
+    PricingError(#[from] PricingError),
+
+    #[error("Missing {0} in the response")]
+    MissingData(&'static str),
+}
+
+#[derive(Error, Debug)]
+pub enum DiscountAnalyticsError {
+    #[error(transparent)]
+    NetworkError(#[from] NetworkError),
+
+    #[error("Missing {0} in the response")]
+    MissingData(&'static str),
+}
+
+#[derive(Error, Debug)]
+pub enum DiscountCampaignError {
+    #[error(transparent)]
+    NetworkError(#[from] NetworkError),
+
+    #[error(transparent)]
+    ValidationError(#[from] ValidationError),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid {field}: {message}")]
+pub struct ValidationError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub(crate) fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
 }
@@ -3,7 +3,11 @@ use reqwest::{
     header::{HeaderMap, HeaderValue},
     Method, Request,
 };
+use std::sync::Arc;
+use utils::{ResponseData, VecResponse};
 
+#[macro_use]
+mod macros;
 pub(crate) mod errors;
 pub(crate) mod modules;
 pub mod utils;
@@ -14,6 +18,7 @@ pub mod types;
 pub struct LemonSqueezy {
     client: reqwest::Client,
     headers: HeaderMap,
+    cache: Option<Arc<cache::Cache>>,
 }
 
 impl LemonSqueezy {
@@ -34,7 +39,43 @@ impl LemonSqueezy {
             HeaderValue::from_str(&format!("Bearer {}", key)).unwrap(),
         );
 
-        Self { client, headers }
+        Self {
+            client,
+            headers,
+            cache: None,
+        }
+    }
+
+    /// Cache the `GET` requests of the resources given a TTL, see `Cache`
+    ///
+    /// ### Example
+    /// ```ignore
+    /// use std::time::Duration;
+    /// use lemonsqueezy::LemonSqueezy;
+    /// use lemonsqueezy::cache::{Cache, InMemoryLruCache};
+    ///
+    /// let api = LemonSqueezy::new(api_key).with_cache(Cache::default());
+    ///
+    /// let api = LemonSqueezy::new(api_key).with_cache(
+    ///     Cache::new(InMemoryLruCache::new(10_000)).ttl("products", Duration::from_secs(3600)),
+    /// );
+    /// ```
+    pub fn with_cache(mut self, cache: cache::Cache) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// The cache set with `LemonSqueezy::with_cache`, to invalidate it
+    pub fn cache(&self) -> Option<&cache::Cache> {
+        self.cache.as_deref()
+    }
+
+    /// Invalidate the resource of a write, called before sending it and once it completed (even if
+    /// it failed) so a `GET` sent in the meantime doesn't cache a stale response
+    fn invalidate_cache(&self, url: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate_write(url);
+        }
     }
 
     /// Get a resource from the LemonSqueezy API
@@ -56,11 +97,58 @@ impl LemonSqueezy {
         &self,
         url: &str,
     ) -> anyhow::Result<T, NetworkError> {
-        let url = format!("{}{}", utils::API_URL, url);
+        let Some((cache, ttl)) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| Some((cache, cache.ttl_for(url)?)))
+        else {
+            return self.get_uncached(url).await;
+        };
+
+        // an entry that no longer deserializes is refetched
+        if let Some(json) = cache.backend().get(url) {
+            if let Ok(json) = serde_json::from_str(&json) {
+                return Ok(json);
+            }
+        }
+
+        let generation = cache.generation(url);
+        let full_url = format!("{}{}", utils::API_URL, url);
+
+        let response = self
+            .client
+            .get(full_url)
+            .headers(self.headers.clone())
+            .send()
+            .await?;
+
+        let success = response.status().is_success();
+        let body = response.text().await?;
+        let json = serde_json::from_str(&body)?;
+
+        if success {
+            cache.set(url, body, ttl, generation);
+        }
+
+        Ok(json)
+    }
+
+    /// Get a resource from the LemonSqueezy API, bypassing the cache
+    ///
+    /// ### Arguments
+    /// url - The URL to send a `GET` request to
+    ///
+    /// ### Returns
+    /// A `Result` containing either the response body as Generic type T or a `NetworkError`
+    pub async fn get_uncached<T: for<'de> serde::Deserialize<'de>>(
+        &self,
+        url: &str,
+    ) -> anyhow::Result<T, NetworkError> {
+        let full_url = format!("{}{}", utils::API_URL, url);
 
         let response = self
             .client
-            .get(url)
+            .get(full_url)
             .headers(self.headers.clone())
             .send()
             .await?;
@@ -70,6 +158,43 @@ impl LemonSqueezy {
         Ok(json)
     }
 
+    /// Get every page of a paginated resource from the LemonSqueezy API
+    ///
+    /// ### Arguments
+    /// url - The URL of the first page to send a `GET` request to
+    ///
+    /// ### Returns
+    /// A `Result` containing either the data of all the pages or a `NetworkError`
+    ///
+    /// ### Example
+    /// ```ignore
+    /// use lemonsqueezy::LemonSqueezy;
+    /// use lemonsqueezy::products::ProductResponse;
+    ///
+    /// let api = LemonSqueezy::new(api_key);
+    /// api.get_all_pages::<ProductResponse>("/v1/products?filter[store_id]=1").await.unwrap();
+    /// ```
+    pub async fn get_all_pages<T: for<'de> serde::Deserialize<'de>>(
+        &self,
+        url: &str,
+    ) -> anyhow::Result<Vec<ResponseData<T>>, NetworkError> {
+        let mut data = Vec::new();
+        let mut next = Some(url.to_string());
+
+        while let Some(url) = next {
+            let response = self.get::<VecResponse<Vec<ResponseData<T>>>>(&url).await?;
+
+            // the `next` link is absolute, while `get` expects a path
+            next = response
+                .next_page()
+                .map(|next| next.trim_start_matches(utils::API_URL).to_string());
+
+            data.extend(response.into_inner_response());
+        }
+
+        Ok(data)
+    }
+
     /// Post a form resource to the LemonSqueezy API
     ///
     /// ### Arguments
@@ -85,15 +210,21 @@ impl LemonSqueezy {
         headers: HeaderMap,
         form: &[(&str, &str)],
     ) -> anyhow::Result<V, NetworkError> {
-        let url = format!("{}{}", utils::API_URL, url);
+        let full_url = format!("{}{}", utils::API_URL, url);
+
+        self.invalidate_cache(url);
 
         let response = self
             .client
-            .post(url)
+            .post(full_url)
             .headers(headers)
             .form(form)
             .send()
-            .await?;
+            .await;
+
+        self.invalidate_cache(url);
+
+        let response = response?;
 
         let response = response.json().await?;
 
@@ -121,15 +252,68 @@ impl LemonSqueezy {
         url: &str,
         body: T,
     ) -> anyhow::Result<V, NetworkError> {
-        let url = format!("{}{}", utils::API_URL, url);
+        let full_url = format!("{}{}", utils::API_URL, url);
+
+        self.invalidate_cache(url);
 
         let response = self
             .client
-            .post(url)
+            .post(full_url)
             .json(&body)
             .headers(self.headers.clone())
             .send()
-            .await?;
+            .await;
+
+        self.invalidate_cache(url);
+
+        let response = response?;
+
+        let response = response.json().await?;
+
+        Ok(response)
+    }
+
+    /// Post a resource to the LemonSqueezy API, with additional headers
+    ///
+    /// ### Arguments
+    /// url - The URL to send a `Post` request to
+    /// headers - Headers to send in addition to the default ones
+    /// body - The body to send with the request
+    ///
+    /// ### Returns
+    /// A `Result` containing either the response body as Generic type T or a `NetworkError`,
+    /// `NetworkError::StatusCodeError` if the response status is not a success
+    pub async fn post_with_headers<
+        V: for<'de> serde::Deserialize<'de>,
+        T: serde::ser::Serialize,
+    >(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+        body: T,
+    ) -> anyhow::Result<V, NetworkError> {
+        let full_url = format!("{}{}", utils::API_URL, url);
+
+        self.invalidate_cache(url);
+
+        let mut request_headers = self.headers.clone();
+        request_headers.extend(headers);
+
+        let response = self
+            .client
+            .post(full_url)
+            .json(&body)
+            .headers(request_headers)
+            .send()
+            .await;
+
+        self.invalidate_cache(url);
+
+        let response = response?;
+
+        if !response.status().is_success() {
+            return Err(NetworkError::StatusCodeError(response.status()));
+        }
 
         let response = response.json().await?;
 
@@ -155,14 +339,20 @@ impl LemonSqueezy {
         &self,
         url: &str,
     ) -> anyhow::Result<V, NetworkError> {
-        let url = format!("{}{}", utils::API_URL, url);
+        let full_url = format!("{}{}", utils::API_URL, url);
+
+        self.invalidate_cache(url);
 
         let response = self
             .client
-            .delete(url)
+            .delete(full_url)
             .headers(self.headers.clone())
             .send()
-            .await?;
+            .await;
+
+        self.invalidate_cache(url);
+
+        let response = response?;
 
         let response = response.json().await?;
 
@@ -189,13 +379,28 @@ impl LemonSqueezy {
         url: &str,
         body: T,
     ) -> anyhow::Result<serde_json::Value, NetworkError> {
-        let url = format!("{}{}", utils::API_URL, url);
+        self.patch_typed(url, body).await
+    }
+
+    /// Update a resource from the LemonSqueezy API, deserializing the response into `V`
+    pub(crate) async fn patch_typed<V: for<'de> serde::Deserialize<'de>, T: Into<reqwest::Body>>(
+        &self,
+        url: &str,
+        body: T,
+    ) -> anyhow::Result<V, NetworkError> {
+        let full_url = format!("{}{}", utils::API_URL, url);
 
-        let mut request = Request::new(Method::PATCH, url.parse().unwrap());
+        self.invalidate_cache(url);
+
+        let mut request = Request::new(Method::PATCH, full_url.parse().unwrap());
         request.headers_mut().extend(self.headers.clone());
         request.body_mut().replace(body.into());
 
-        let response = self.client.execute(request).await?;
+        let response = self.client.execute(request).await;
+
+        self.invalidate_cache(url);
+
+        let response = response?;
 
         let response = response.json().await?;
 
@@ -0,0 +1,411 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Stores the bodies of cached `GET` responses, by URL
+///
+/// Implement it to share the cache between processes, e.g. with Redis. The keys don't contain the
+/// API key, don't share a backend between clients of different accounts.
+pub trait CacheBackend: Send + Sync {
+    fn get(&self, key: &str) -> Option<String>;
+
+    fn set(&self, key: &str, value: String, ttl: Duration);
+
+    fn remove(&self, key: &str);
+
+    /// Remove every key starting with `prefix`
+    fn remove_prefix(&self, prefix: &str);
+}
+
+struct LruEntry {
+    value: String,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct LruState {
+    entries: HashMap<String, LruEntry>,
+    // last use -> key, the first entry is the least recently used
+    order: BTreeMap<u64, String>,
+    clock: u64,
+}
+
+impl LruState {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.last_used);
+        }
+    }
+}
+
+/// In-memory cache evicting the least recently used entry once `capacity` is reached
+pub struct InMemoryLruCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+impl InMemoryLruCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// The number of entries, including the expired ones not evicted yet
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CacheBackend for InMemoryLruCache {
+    fn get(&self, key: &str) -> Option<String> {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        let entry = state.entries.get_mut(key)?;
+
+        if entry.expires_at <= Instant::now() {
+            state.remove(key);
+            return None;
+        }
+
+        state.clock += 1;
+        state.order.remove(&entry.last_used);
+        entry.last_used = state.clock;
+        state.order.insert(state.clock, key.to_string());
+
+        Some(entry.value.clone())
+    }
+
+    fn set(&self, key: &str, value: String, ttl: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.remove(key);
+
+        while state.entries.len() >= self.capacity {
+            let Some((_, oldest)) = state.order.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+
+        state.clock += 1;
+        let last_used = state.clock;
+        state.order.insert(last_used, key.to_string());
+        state.entries.insert(
+            key.to_string(),
+            LruEntry {
+                value,
+                expires_at: Instant::now() + ttl,
+                last_used,
+            },
+        );
+    }
+
+    fn remove(&self, key: &str) {
+        self.state.lock().unwrap().remove(key);
+    }
+
+    fn remove_prefix(&self, prefix: &str) {
+        let mut state = self.state.lock().unwrap();
+
+        let keys = state
+            .entries
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for key in keys {
+            state.remove(&key);
+        }
+    }
+}
+
+/// Read-through cache of the `GET` requests, enabled with `LemonSqueezy::with_cache`
+///
+/// Only the resources given a TTL are cached. They are invalidated when the client writes to
+/// them, and can be invalidated explicitly or from the webhook events. A response fetched while
+/// its resource is being written to or invalidated is not cached, this only holds within the
+/// process: another process sharing the backend can still cache a stale response.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use lemonsqueezy::cache::{Cache, CacheBackend, InMemoryLruCache};
+///
+/// let cache = Cache::new(InMemoryLruCache::new(100)).ttl("products", Duration::from_secs(60));
+///
+/// assert_eq!(cache.ttl_for("/v1/products/1"), Some(Duration::from_secs(60)));
+/// assert_eq!(cache.ttl_for("/v1/products?filter[store_id]=1"), Some(Duration::from_secs(60)));
+/// assert_eq!(cache.ttl_for("/v1/orders/1"), None);
+///
+/// cache.backend().set("/v1/products/1", "{}".to_string(), Duration::from_secs(60));
+/// cache.invalidate_resource("products");
+/// assert_eq!(cache.backend().get("/v1/products/1"), None);
+/// ```
+pub struct Cache {
+    backend: Box<dyn CacheBackend>,
+    ttls: HashMap<String, Duration>,
+    // resource -> number of invalidations, a response is only cached if it didn't change while
+    // the response was fetched
+    generations: Mutex<HashMap<String, u64>>,
+}
+
+impl Cache {
+    /// A cache without any TTL, add them with `Cache::ttl`
+    pub fn new(backend: impl CacheBackend + 'static) -> Self {
+        Self {
+            backend: Box::new(backend),
+            ttls: HashMap::new(),
+            generations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cache the `GET` requests of a resource, e.g. `products` or `subscription-items`
+    pub fn ttl(mut self, resource: impl Into<String>, ttl: Duration) -> Self {
+        self.ttls.insert(resource.into(), ttl);
+        self
+    }
+
+    pub fn backend(&self) -> &dyn CacheBackend {
+        self.backend.as_ref()
+    }
+
+    /// The TTL of the resource requested by a URL, `None` if it is not cached
+    pub fn ttl_for(&self, url: &str) -> Option<Duration> {
+        self.ttls.get(resource(url)?).copied()
+    }
+
+    /// Invalidate a single URL
+    pub fn invalidate(&self, url: &str) {
+        self.backend.remove(url);
+    }
+
+    /// Invalidate every cached URL of a resource, e.g. `products`
+    ///
+    /// `license-keys` doesn't invalidate `license-key-instances`, only `/v1/license-keys`,
+    /// `/v1/license-keys/...` and `/v1/license-keys?...`.
+    pub fn invalidate_resource(&self, resource: &str) {
+        let mut generations = self.generations.lock().unwrap();
+        *generations.entry(resource.to_string()).or_insert(0) += 1;
+
+        self.backend.remove(&format!("/v1/{}", resource));
+        self.backend.remove_prefix(&format!("/v1/{}/", resource));
+        self.backend.remove_prefix(&format!("/v1/{}?", resource));
+    }
+
+    /// Invalidate the resources changed by a webhook event, to call from the webhook handler
+    ///
+    /// # Arguments
+    /// - event_name: The `meta.event_name` of the event, e.g. `subscription_updated`
+    ///
+    /// # Example
+    /// ```ignore
+    /// let event: WebhookSuscriptionEvent<Value> = serde_json::from_slice(&body)?;
+    ///
+    /// if let Some(cache) = lemonsqueezy.cache() {
+    ///     cache.invalidate_event(&event.meta.event_name);
+    /// }
+    /// ```
+    pub fn invalidate_event(&self, event_name: &str) {
+        let resources: &[&str] = match event_name {
+            "order_created" | "order_refunded" => &[
+                "orders",
+                "order-items",
+                "customers",
+                "discounts",
+                "discount-redemptions",
+                "license-keys",
+                "subscriptions",
+            ],
+            "license_key_created" | "license_key_updated" => {
+                &["license-keys", "license-key-instances"]
+            }
+            event if event.starts_with("subscription_") => &[
+                "subscriptions",
+                "subscription-items",
+                "subscription-invoices",
+                "usage-records",
+                "customers",
+            ],
+            _ => &[],
+        };
+
+        for resource in resources {
+            self.invalidate_resource(resource);
+        }
+    }
+
+    /// Invalidate the resource a write was sent to, before sending it and once it completed
+    pub(crate) fn invalidate_write(&self, url: &str) {
+        if let Some(resource) = resource(url) {
+            self.invalidate_resource(resource);
+        }
+    }
+
+    /// The generation of the resource of a URL, to read before fetching it
+    pub(crate) fn generation(&self, url: &str) -> u64 {
+        let generations = self.generations.lock().unwrap();
+
+        resource(url)
+            .and_then(|resource| generations.get(resource))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Cache a response, unless its resource was invalidated since `generation` was read
+    pub(crate) fn set(&self, url: &str, body: String, ttl: Duration, generation: u64) {
+        let generations = self.generations.lock().unwrap();
+
+        let current = resource(url)
+            .and_then(|resource| generations.get(resource))
+            .copied()
+            .unwrap_or(0);
+
+        if current == generation {
+            self.backend.set(url, body, ttl);
+        }
+    }
+}
+
+impl Default for Cache {
+    /// An in-memory LRU cache of 1000 entries, caching products, variants, prices, stores and files
+    /// for 5 minutes
+    fn default() -> Self {
+        let ttl = Duration::from_secs(5 * 60);
+
+        Self::new(InMemoryLruCache::new(1_000))
+            .ttl("products", ttl)
+            .ttl("variants", ttl)
+            .ttl("prices", ttl)
+            .ttl("stores", ttl)
+            .ttl("files", ttl)
+    }
+}
+
+impl fmt::Debug for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache").field("ttls", &self.ttls).finish()
+    }
+}
+
+/// The resource of a URL, `products` for `/v1/products/1?include=variants`
+fn resource(url: &str) -> Option<&str> {
+    url.strip_prefix("/v1/")?
+        .split(['/', '?'])
+        .next()
+        .filter(|resource| !resource.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let lru = InMemoryLruCache::new(2);
+        lru.set("a", "1".to_string(), TTL);
+        lru.set("b", "2".to_string(), TTL);
+
+        // `a` becomes the most recently used
+        assert_eq!(lru.get("a"), Some("1".to_string()));
+        lru.set("c", "3".to_string(), TTL);
+
+        assert_eq!(lru.len(), 2);
+        assert_eq!(lru.get("a"), Some("1".to_string()));
+        assert_eq!(lru.get("b"), None);
+        assert_eq!(lru.get("c"), Some("3".to_string()));
+    }
+
+    #[test]
+    fn replacing_an_entry_does_not_evict() {
+        let lru = InMemoryLruCache::new(2);
+        lru.set("a", "1".to_string(), TTL);
+        lru.set("b", "2".to_string(), TTL);
+        lru.set("a", "3".to_string(), TTL);
+
+        assert_eq!(lru.get("a"), Some("3".to_string()));
+        assert_eq!(lru.get("b"), Some("2".to_string()));
+    }
+
+    #[test]
+    fn expired_entries_are_not_returned() {
+        let lru = InMemoryLruCache::new(2);
+        lru.set("a", "1".to_string(), Duration::ZERO);
+        lru.set("b", "2".to_string(), TTL);
+
+        assert_eq!(lru.get("a"), None);
+        assert_eq!(lru.len(), 1);
+        assert_eq!(lru.get("b"), Some("2".to_string()));
+    }
+
+    #[test]
+    fn a_zero_capacity_caches_nothing() {
+        let lru = InMemoryLruCache::new(0);
+        lru.set("a", "1".to_string(), TTL);
+
+        assert!(lru.is_empty());
+    }
+
+    #[test]
+    fn writes_invalidate_their_resource_only() {
+        let cache = Cache::new(InMemoryLruCache::new(10));
+        for url in [
+            "/v1/license-keys",
+            "/v1/license-keys/1",
+            "/v1/license-keys?filter[store_id]=1",
+            "/v1/license-key-instances/1",
+            "/v1/license-keysets/1",
+        ] {
+            cache.backend().set(url, "{}".to_string(), TTL);
+        }
+
+        cache.invalidate_write("/v1/license-keys/1");
+
+        assert_eq!(cache.backend().get("/v1/license-keys"), None);
+        assert_eq!(cache.backend().get("/v1/license-keys/1"), None);
+        assert_eq!(
+            cache.backend().get("/v1/license-keys?filter[store_id]=1"),
+            None
+        );
+        assert!(cache.backend().get("/v1/license-key-instances/1").is_some());
+        assert!(cache.backend().get("/v1/license-keysets/1").is_some());
+    }
+
+    #[test]
+    fn responses_fetched_during_a_write_are_not_cached() {
+        let cache = Cache::new(InMemoryLruCache::new(10));
+
+        // a read starts, then a write to the same resource goes through before it completes
+        let generation = cache.generation("/v1/subscriptions/1");
+        cache.invalidate_write("/v1/subscriptions/1");
+        cache.set("/v1/subscriptions/1", "{}".to_string(), TTL, generation);
+
+        assert_eq!(cache.backend().get("/v1/subscriptions/1"), None);
+
+        // other resources are not affected
+        let generation = cache.generation("/v1/customers/1");
+        cache.set("/v1/customers/1", "{}".to_string(), TTL, generation);
+
+        assert!(cache.backend().get("/v1/customers/1").is_some());
+
+        // a read started after the write is cached
+        let generation = cache.generation("/v1/subscriptions/1");
+        cache.set("/v1/subscriptions/1", "{}".to_string(), TTL, generation);
+
+        assert!(cache.backend().get("/v1/subscriptions/1").is_some());
+    }
+}
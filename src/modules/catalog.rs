@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::files::FileResponse;
+use crate::prices::PriceResponse;
+use crate::products::ProductResponse;
+use crate::variants::{VariantResponse, VariantStatus};
+
+/// The products of a store, with their variants, prices and files
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Catalog {
+    pub store_id: i64,
+    pub products: Vec<CatalogProduct>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogProduct {
+    pub id: i64,
+    pub attributes: ProductResponse,
+    /// The published variants, in their `sort` order
+    pub variants: Vec<CatalogVariant>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogVariant {
+    pub id: i64,
+    pub attributes: VariantResponse,
+    /// The current price of the variant, the one with the highest ID
+    pub price: Option<PriceResponse>,
+    /// The files, in their `sort` order
+    pub files: Vec<FileResponse>,
+}
+
+impl Catalog {
+    /// Fetch every product, variant, price and file of a store and join them
+    ///
+    /// The variants are fetched per product, and the prices and files per variant. Variants that
+    /// are still a draft or pending are left out.
+    ///
+    /// # Arguments
+    /// - api: The LemonSqueezy client
+    /// - store_id: The ID of the store
+    ///
+    /// # Returns
+    /// - `anyhow::Result<Catalog, crate::errors::NetworkError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::catalog::Catalog;
+    ///
+    /// let catalog = Catalog::load(&lemonsqueezy, 1).await?;
+    /// std::fs::write("catalog.json", catalog.to_json()?)?;
+    /// ```
+    pub async fn load(
+        api: &crate::LemonSqueezy,
+        store_id: i64,
+    ) -> anyhow::Result<Self, crate::errors::NetworkError> {
+        let products = api
+            .get_all_pages::<ProductResponse>(&format!(
+                "/v1/products?filter[store_id]={}&page[size]=100",
+                store_id
+            ))
+            .await?;
+
+        let products: Vec<(i64, ProductResponse)> = products
+            .into_iter()
+            .filter_map(|product| Some((product.id.parse().ok()?, product.attributes)))
+            .collect();
+
+        // variants, prices and files can't be filtered by store, they are fetched per product and
+        // per variant
+        let mut variants = Vec::new();
+        for (product_id, _) in &products {
+            let product_variants = api
+                .get_all_pages::<VariantResponse>(&format!(
+                    "/v1/variants?filter[product_id]={}&page[size]=100",
+                    product_id
+                ))
+                .await?;
+
+            variants.extend(
+                product_variants
+                    .into_iter()
+                    .filter_map(|variant| Some((variant.id.parse().ok()?, variant.attributes)))
+                    .filter(|(_, variant)| is_listed(variant)),
+            );
+        }
+
+        let mut prices = Vec::new();
+        let mut files = Vec::new();
+        for (variant_id, _) in &variants {
+            let variant_prices = api
+                .get_all_pages::<PriceResponse>(&format!(
+                    "/v1/prices?filter[variant_id]={}&page[size]=100",
+                    variant_id
+                ))
+                .await?;
+            let variant_files = api
+                .get_all_pages::<FileResponse>(&format!(
+                    "/v1/files?filter[variant_id]={}&page[size]=100",
+                    variant_id
+                ))
+                .await?;
+
+            prices.extend(
+                variant_prices
+                    .into_iter()
+                    .filter_map(|price| Some((price.id.parse().ok()?, price.attributes))),
+            );
+            files.extend(variant_files.into_iter().map(|file| file.attributes));
+        }
+
+        Ok(Self::from_parts(
+            store_id, products, variants, prices, files,
+        ))
+    }
+
+    /// Join products, variants, prices and files into a catalog
+    ///
+    /// Products of other stores, draft and pending variants, and the prices and files of the
+    /// variants left out are ignored. A variant keeps a price row per price change, only the
+    /// latest one (highest ID) is kept as the price of the variant.
+    ///
+    /// # Arguments
+    /// - store_id: The ID of the store
+    /// - products: The products, with their ID
+    /// - variants: The variants, with their ID
+    /// - prices: The prices, with their ID
+    /// - files: The files
+    ///
+    /// # Returns
+    /// - `Catalog` object
+    ///
+    /// # Example
+    /// ```
+    /// use lemonsqueezy::catalog::Catalog;
+    /// use lemonsqueezy::prices::PriceResponse;
+    /// use lemonsqueezy::products::ProductResponse;
+    /// use lemonsqueezy::variants::{VariantResponse, VariantStatus};
+    ///
+    /// let variant = |product_id, status: VariantStatus| VariantResponse {
+    ///     product_id,
+    ///     status: Some(status),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let catalog = Catalog::from_parts(
+    ///     1,
+    ///     vec![(10, ProductResponse { store_id: 1, ..Default::default() })],
+    ///     vec![
+    ///         (100, variant(10, VariantStatus::Published)),
+    ///         (101, variant(10, VariantStatus::Pending)),
+    ///     ],
+    ///     vec![
+    ///         (1001, PriceResponse { variant_id: 100, unit_price: 1299, ..Default::default() }),
+    ///         (1000, PriceResponse { variant_id: 100, unit_price: 999, ..Default::default() }),
+    ///     ],
+    ///     vec![],
+    /// );
+    ///
+    /// assert_eq!(catalog.products[0].variants.len(), 1);
+    /// assert_eq!(catalog.products[0].variants[0].price.as_ref().unwrap().unit_price, 1299);
+    ///
+    /// let json = catalog.to_json().unwrap();
+    /// assert_eq!(Catalog::from_json(&json).unwrap(), catalog);
+    /// ```
+    pub fn from_parts(
+        store_id: i64,
+        products: Vec<(i64, ProductResponse)>,
+        variants: Vec<(i64, VariantResponse)>,
+        prices: Vec<(i64, PriceResponse)>,
+        files: Vec<FileResponse>,
+    ) -> Self {
+        let mut prices_by_variant = HashMap::<i64, (i64, PriceResponse)>::new();
+        for (id, price) in prices {
+            match prices_by_variant.get(&price.variant_id) {
+                Some((latest_id, _)) if *latest_id >= id => {}
+                _ => {
+                    prices_by_variant.insert(price.variant_id, (id, price));
+                }
+            }
+        }
+
+        let mut files_by_variant = HashMap::<i64, Vec<FileResponse>>::new();
+        for file in files {
+            files_by_variant
+                .entry(file.variant_id)
+                .or_default()
+                .push(file);
+        }
+
+        let mut variants_by_product = HashMap::<i64, Vec<CatalogVariant>>::new();
+        for (id, variant) in variants {
+            if !is_listed(&variant) {
+                continue;
+            }
+
+            let mut files = files_by_variant.remove(&id).unwrap_or_default();
+            files.sort_by_key(|file| file.sort);
+
+            variants_by_product
+                .entry(variant.product_id)
+                .or_default()
+                .push(CatalogVariant {
+                    id,
+                    attributes: variant,
+                    price: prices_by_variant.remove(&id).map(|(_, price)| price),
+                    files,
+                });
+        }
+
+        let products = products
+            .into_iter()
+            .filter(|(_, product)| product.store_id == store_id)
+            .map(|(id, product)| {
+                let mut variants = variants_by_product.remove(&id).unwrap_or_default();
+                variants.sort_by_key(|variant| variant.attributes.sort);
+
+                CatalogProduct {
+                    id,
+                    attributes: product,
+                    variants,
+                }
+            })
+            .collect();
+
+        Self { store_id, products }
+    }
+
+    /// Serialize the catalog to JSON, e.g. for a static site build
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Reload a catalog serialized with `Catalog::to_json`
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Whether a variant is part of the catalog, drafts and pending variants are not
+fn is_listed(variant: &VariantResponse) -> bool {
+    !matches!(
+        variant.status,
+        Some(VariantStatus::Draft) | Some(VariantStatus::Pending)
+    )
+}
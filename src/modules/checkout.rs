@@ -17,6 +17,25 @@ impl Checkout {
         Self { api }
     }
 
+    /// Start building a checkout for a variant of a store, see `CheckoutBuilder`
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::checkout::Checkout;
+    ///
+    /// let data = Checkout::builder(1, 2)
+    ///     .email("john@example.com")
+    ///     .redirect_url("https://example.com/thanks")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let checkout = Checkout::build(lemonsqueezy);
+    /// let checkout = checkout.create(data).await;
+    /// ```
+    pub fn builder(store_id: i64, variant_id: i64) -> CheckoutBuilder {
+        CheckoutBuilder::new(store_id, variant_id)
+    }
+
     /// Retrieve a checkout
     ///
     /// # Arguments
@@ -110,4 +129,38 @@ impl Checkout {
 
         Ok(response)
     }
+
+    /// Create a checkout with `preview: true`, to show the localized totals before redirecting
+    ///
+    /// # Arguments
+    /// - data: The checkout data
+    ///
+    /// # Returns
+    /// - `anyhow::Result<Option<Preview>, crate::errors::NetworkError>` object, `None` if the
+    ///   API did not return a preview
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::checkout::Checkout;
+    ///
+    /// let checkout = Checkout::build(lemonsqueezy);
+    /// let data = Checkout::builder(store_id, variant_id)
+    ///     .billing_address("DE", "10115")
+    ///     .build()?;
+    ///
+    /// let preview = checkout.preview(data).await?.unwrap();
+    /// println!("{} incl. {} tax", preview.total_formatted, preview.tax_formatted);
+    /// ```
+    pub async fn preview(
+        &self,
+        mut data: CreateCheckout,
+    ) -> anyhow::Result<Option<Preview>, crate::errors::NetworkError> {
+        data.attributes.preview = Some(true);
+
+        let response = self.create(data).await?;
+
+        Ok(response
+            .get_inner_response()
+            .and_then(|checkout| checkout.preview.clone()))
+    }
 }
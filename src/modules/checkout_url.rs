@@ -0,0 +1,145 @@
+pub use crate::errors::ValidationError;
+
+use reqwest::Url;
+use serde_json::Value;
+
+use crate::checkout::{CreateCheckoutCheckoutData, CreateCheckoutCheckoutOptions};
+
+/// Build a prefilled hosted checkout URL, without any API call
+///
+/// The checkout data is passed as `checkout[...]` query parameters and the checkout options as
+/// `embed`, `media`, `logo`, `desc`, `discount`, `dark` and `button_color`. The custom data must
+/// be a flat JSON object, each value is sent as `checkout[custom][key]`.
+///
+/// # Arguments
+/// - buy_now_url: The `buy_now_url` of the product, or the share URL of a variant
+/// - enabled_variants: The variants that can be picked in the checkout, all of them if empty
+/// - checkout_options: The checkout options
+/// - checkout_data: The data to prefill
+///
+/// # Returns
+/// - `Result<String, ValidationError>` object
+///
+/// Read More: https://docs.lemonsqueezy.com/help/checkout/prefilling-checkout-fields
+///
+/// # Example
+/// ```
+/// use lemonsqueezy::checkout::{CreateCheckoutCheckoutData, CreateCheckoutCheckoutOptions};
+/// use lemonsqueezy::checkout_url;
+///
+/// let url = checkout_url::build(
+///     "https://store.lemonsqueezy.com/checkout/buy/a1b2c3",
+///     &[1, 2],
+///     &CreateCheckoutCheckoutOptions {
+///         dark: Some(true),
+///         logo: Some(false),
+///         button_color: Some("#7047EB".to_string()),
+///         ..Default::default()
+///     },
+///     &CreateCheckoutCheckoutData {
+///         email: Some("john+test@example.com".to_string()),
+///         custom: Some(serde_json::json!({ "user_id": 123 })),
+///         ..Default::default()
+///     },
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     url,
+///     "https://store.lemonsqueezy.com/checkout/buy/a1b2c3?enabled=1%2C2\
+///      &checkout%5Bemail%5D=john%2Btest%40example.com\
+///      &checkout%5Bcustom%5D%5Buser_id%5D=123\
+///      &logo=0&dark=1&button_color=%237047EB"
+/// );
+/// ```
+pub fn build(
+    buy_now_url: &str,
+    enabled_variants: &[i64],
+    checkout_options: &CreateCheckoutCheckoutOptions,
+    checkout_data: &CreateCheckoutCheckoutData,
+) -> Result<String, ValidationError> {
+    let mut url = Url::parse(buy_now_url)
+        .map_err(|error| ValidationError::new("buy_now_url", error.to_string()))?;
+
+    {
+        let mut query = url.query_pairs_mut();
+
+        if !enabled_variants.is_empty() {
+            let enabled_variants = enabled_variants
+                .iter()
+                .map(|variant_id| variant_id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            query.append_pair("enabled", &enabled_variants);
+        }
+
+        let fields = [
+            ("checkout[email]", &checkout_data.email),
+            ("checkout[name]", &checkout_data.name),
+            ("checkout[tax_number]", &checkout_data.tax_number),
+            ("checkout[discount_code]", &checkout_data.discount_code),
+        ];
+
+        for (name, value) in fields {
+            if let Some(value) = value {
+                query.append_pair(name, value);
+            }
+        }
+
+        if let Some(billing_address) = &checkout_data.billing_address {
+            query.append_pair(
+                "checkout[billing_address][country]",
+                &billing_address.country,
+            );
+            query.append_pair("checkout[billing_address][zip]", &billing_address.zip);
+        }
+
+        match &checkout_data.custom {
+            None => {}
+            Some(Value::Object(custom)) => {
+                for (key, value) in custom {
+                    let value = match value {
+                        Value::Null => continue,
+                        Value::String(value) => value.clone(),
+                        Value::Bool(_) | Value::Number(_) => value.to_string(),
+                        Value::Array(_) | Value::Object(_) => {
+                            return Err(ValidationError::new(
+                                "custom",
+                                format!("{} must be a string, a number or a boolean", key),
+                            ))
+                        }
+                    };
+
+                    query.append_pair(&format!("checkout[custom][{}]", key), &value);
+                }
+            }
+            Some(_) => return Err(ValidationError::new("custom", "must be a JSON object")),
+        }
+
+        let options = [
+            ("embed", checkout_options.embed),
+            ("media", checkout_options.media),
+            ("logo", checkout_options.logo),
+            ("desc", checkout_options.desc),
+            ("discount", checkout_options.discount),
+            ("dark", checkout_options.dark),
+        ];
+
+        for (name, value) in options {
+            if let Some(value) = value {
+                query.append_pair(name, if value { "1" } else { "0" });
+            }
+        }
+
+        if let Some(button_color) = &checkout_options.button_color {
+            query.append_pair("button_color", button_color);
+        }
+    }
+
+    if url.query() == Some("") {
+        url.set_query(None);
+    }
+
+    Ok(url.into())
+}
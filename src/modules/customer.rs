@@ -1,14 +1,15 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::money::Money;
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CustomerResponse {
     pub store_id: i64,
     pub name: String,
     pub email: String,
-    pub status: String,
+    pub status: CustomerStatus,
     pub city: Value,
     pub region: Value,
     pub country: String,
@@ -18,11 +19,42 @@ pub struct CustomerResponse {
     pub country_formatted: String,
     pub total_revenue_currency_formatted: String,
     pub mrr_formatted: String,
-    pub created_at: String,
-    pub updated_at: String,
+    #[serde(default)]
+    pub urls: Urls,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
     pub test_mode: bool,
 }
 
+string_enum! {
+    pub enum CustomerStatus {
+        Subscribed => "subscribed",
+        Unsubscribed => "unsubscribed",
+        Archived => "archived",
+        RequiresVerification => "requires_verification",
+        InvalidEmail => "invalid_email",
+        Bounced => "bounced",
+    }
+}
+
+impl CustomerResponse {
+    /// The monthly recurring revenue, in the currency of the store
+    pub fn mrr_money(&self, store_currency: &str) -> Money {
+        Money::new(self.mrr, store_currency)
+    }
+
+    /// The total revenue, in the currency of the store
+    pub fn total_revenue_money(&self, store_currency: &str) -> Money {
+        Money::new(self.total_revenue_currency, store_currency)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Urls {
+    /// Signed URL to the customer portal, valid for 24 hours
+    pub customer_portal: Option<String>,
+}
+
 pub struct CustomerFilters {
     pub store_id: Option<i64>,
     pub email: Option<String>,
@@ -113,4 +145,35 @@ impl Customer {
 
         Ok(response)
     }
+
+    /// Get a fresh signed URL to the customer portal of a customer
+    ///
+    /// The URLs returned by the API expire after 24 hours, so the customer is fetched again,
+    /// bypassing the cache.
+    ///
+    /// # Arguments
+    /// * `customer_id` - The customer id
+    ///
+    /// # Returns
+    /// `Result<Option<String>, crate::errors::NetworkError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::customer::Customer;
+    /// let customer = Customer::build(lemonsqueezy);
+    /// let url = customer.customer_portal_url(123).await.unwrap();
+    /// ```
+    pub async fn customer_portal_url(
+        &self,
+        customer_id: usize,
+    ) -> anyhow::Result<Option<String>, crate::errors::NetworkError> {
+        let response = self
+            .api
+            .get_uncached::<Response<CustomerResponse>>(&format!("/v1/customers/{}", customer_id))
+            .await?;
+
+        Ok(response
+            .get_inner_response()
+            .and_then(|customer| customer.urls.customer_portal.clone()))
+    }
 }
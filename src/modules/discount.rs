@@ -1,9 +1,9 @@
 pub use crate::types::discount::*;
 
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::json;
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DiscountResponse {
@@ -11,18 +11,25 @@ pub struct DiscountResponse {
     pub name: String,
     pub code: String,
     pub amount: i64,
-    pub amount_type: String,
+    pub amount_type: AmountType,
     pub is_limited_to_products: bool,
     pub is_limited_redemptions: bool,
     pub max_redemptions: i64,
-    pub starts_at: Value,
-    pub expires_at: Value,
-    pub duration: String,
+    pub starts_at: Option<Timestamp>,
+    pub expires_at: Option<Timestamp>,
+    pub duration: DiscountDuration,
     pub duration_in_months: i64,
-    pub status: String,
+    pub status: DiscountStatus,
     pub status_formatted: String,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+string_enum! {
+    pub enum DiscountStatus {
+        Draft => "draft",
+        Published => "published",
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -37,8 +44,7 @@ pub struct CreateDiscountAttributes {
     pub name: String,
     pub code: String,
     pub amount: i64,
-    /// percent or fixed
-    pub amount_type: String,
+    pub amount_type: AmountType,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_limited_to_products: Option<bool>,
@@ -50,9 +56,10 @@ pub struct CreateDiscountAttributes {
     pub starts_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<String>,
-    /// once, repeating, forever
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub duration: Option<String>,
+    pub duration: Option<DiscountDuration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_in_months: Option<i64>,
 }
 
 pub struct DiscountFilters {
@@ -68,6 +75,25 @@ impl Discount {
         Self { api }
     }
 
+    /// Start building a discount for a store, see `DiscountBuilder`
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::discount::{AmountType, Discount};
+    ///
+    /// let data = Discount::builder(1)
+    ///     .name("Launch")
+    ///     .code("LAUNCH20")
+    ///     .amount(20, AmountType::Percent)
+    ///     .build()?;
+    ///
+    /// let discount = Discount::build(lemonsqueezy);
+    /// let discount = discount.create(data).await;
+    /// ```
+    pub fn builder(store_id: i64) -> DiscountBuilder {
+        DiscountBuilder::new(store_id)
+    }
+
     /// Retrieve a discount
     ///
     /// # Arguments
@@ -164,7 +190,7 @@ impl Discount {
     ///
     /// # Example
     /// ```
-    /// use lemonsqueezy::discount::{Discount, CreateDiscount, CreateDiscountAttributes, CreateDiscountRelationships};
+    /// use lemonsqueezy::discount::{AmountType, Discount, CreateDiscount, CreateDiscountAttributes, CreateDiscountRelationships};
     /// let discount = Discount::build(lemonsqueezy);
     /// let data = CreateDiscount {
     ///    r#type: "discounts".to_string(),
@@ -172,7 +198,7 @@ impl Discount {
     ///       name: "Test".to_string(),
     ///       code: "TEST".to_string(),
     ///       amount: 100,
-    ///       amount_type: "fixed".to_string(),
+    ///       amount_type: AmountType::Fixed,
     ///       ..Default::default()
     ///     },
     ///     relationships: CreateDiscountRelationships {
@@ -0,0 +1,251 @@
+pub use crate::errors::DiscountAnalyticsError;
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::discount::DiscountResponse;
+use crate::discount_redemptions::DiscountRedemptionsResponse;
+use crate::orders::OrderResponse;
+use crate::utils::Response;
+
+/// The redemptions of a discount, amounts are in USD cents
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscountSummary {
+    pub discount_id: i64,
+    pub code: String,
+    pub redemptions: i64,
+    /// Sum of the `discount_total_usd` of the orders
+    pub total_discounted_usd: i64,
+    /// Sum of the `total_usd` of the orders, minus their refunds
+    pub revenue_usd: i64,
+    /// `None` if the number of redemptions is not limited
+    pub remaining_redemptions: Option<i64>,
+}
+
+/// The redemptions of all the discounts of a store, amounts are in USD cents
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreDiscountSummary {
+    pub store_id: i64,
+    pub redemptions: i64,
+    pub total_discounted_usd: i64,
+    pub revenue_usd: i64,
+    pub discounts: Vec<DiscountSummary>,
+}
+
+/// Summarize the redemptions of a discount
+///
+/// # Arguments
+/// - discount_id: The ID of the discount
+/// - discount: The discount
+/// - redemptions: The redemptions of the discount, the others are ignored
+/// - orders: The orders of the redemptions, by ID
+///
+/// # Returns
+/// - `DiscountSummary` object
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use lemonsqueezy::discount::DiscountResponse;
+/// use lemonsqueezy::discount_analytics::summarize;
+/// use lemonsqueezy::discount_redemptions::DiscountRedemptionsResponse;
+/// use lemonsqueezy::orders::OrderResponse;
+///
+/// let discount = DiscountResponse {
+///     code: "LAUNCH".to_string(),
+///     is_limited_redemptions: true,
+///     max_redemptions: 10,
+///     ..Default::default()
+/// };
+/// let redemptions = vec![
+///     DiscountRedemptionsResponse { discount_id: 1, order_id: 7, ..Default::default() },
+///     DiscountRedemptionsResponse { discount_id: 1, order_id: 8, ..Default::default() },
+/// ];
+/// let orders = HashMap::from([
+///     (7, OrderResponse { discount_total_usd: Some(200), total_usd: Some(800), ..Default::default() }),
+///     (8, OrderResponse {
+///         discount_total_usd: Some(200),
+///         total_usd: Some(800),
+///         refunded_amount_usd: Some(800),
+///         ..Default::default()
+///     }),
+/// ]);
+///
+/// let summary = summarize(1, &discount, &redemptions, &orders);
+/// assert_eq!(summary.redemptions, 2);
+/// assert_eq!(summary.total_discounted_usd, 400);
+/// assert_eq!(summary.revenue_usd, 800);
+/// assert_eq!(summary.remaining_redemptions, Some(8));
+/// ```
+pub fn summarize(
+    discount_id: i64,
+    discount: &DiscountResponse,
+    redemptions: &[DiscountRedemptionsResponse],
+    orders: &HashMap<i64, OrderResponse>,
+) -> DiscountSummary {
+    let mut summary = DiscountSummary {
+        discount_id,
+        code: discount.code.clone(),
+        ..Default::default()
+    };
+
+    for redemption in redemptions
+        .iter()
+        .filter(|redemption| redemption.discount_id == discount_id)
+    {
+        summary.redemptions += 1;
+
+        if let Some(order) = orders.get(&redemption.order_id) {
+            summary.total_discounted_usd += order.discount_total_usd.unwrap_or(0);
+            summary.revenue_usd +=
+                order.total_usd.unwrap_or(0) - order.refunded_amount_usd.unwrap_or(0);
+        }
+    }
+
+    if discount.is_limited_redemptions {
+        summary.remaining_redemptions =
+            Some((discount.max_redemptions - summary.redemptions).max(0));
+    }
+
+    summary
+}
+
+pub struct DiscountAnalytics {
+    pub(crate) api: crate::LemonSqueezy,
+}
+
+impl DiscountAnalytics {
+    pub fn build(api: crate::LemonSqueezy) -> Self {
+        Self { api }
+    }
+
+    /// Summarize the redemptions of a discount, fetching the order of each redemption
+    ///
+    /// # Arguments
+    /// - discount_id: The ID of the discount
+    ///
+    /// # Returns
+    /// - `anyhow::Result<DiscountSummary, DiscountAnalyticsError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::discount_analytics::DiscountAnalytics;
+    ///
+    /// let analytics = DiscountAnalytics::build(lemonsqueezy);
+    /// let summary = analytics.discount(1).await?;
+    /// ```
+    pub async fn discount(
+        &self,
+        discount_id: i64,
+    ) -> anyhow::Result<DiscountSummary, DiscountAnalyticsError> {
+        let discount = self
+            .api
+            .get::<Response<DiscountResponse>>(&format!("/v1/discounts/{}", discount_id))
+            .await?;
+        let discount = discount
+            .get_inner_response()
+            .ok_or(DiscountAnalyticsError::MissingData("discount"))?;
+
+        let redemptions = self
+            .api
+            .get_all_pages::<DiscountRedemptionsResponse>(&format!(
+                "/v1/discount-redemptions?filter[discount_id]={}",
+                discount_id
+            ))
+            .await?
+            .into_iter()
+            .map(|redemption| redemption.attributes)
+            .collect::<Vec<_>>();
+
+        let mut orders = HashMap::new();
+        let order_ids = redemptions
+            .iter()
+            .map(|redemption| redemption.order_id)
+            .collect::<HashSet<_>>();
+
+        for order_id in order_ids {
+            let order = self
+                .api
+                .get::<Response<OrderResponse>>(&format!("/v1/orders/{}", order_id))
+                .await?;
+
+            if let Some(order) = order.get_inner_response() {
+                orders.insert(order_id, order.clone());
+            }
+        }
+
+        Ok(summarize(discount_id, discount, &redemptions, &orders))
+    }
+
+    /// Summarize the redemptions of all the discounts of a store
+    ///
+    /// Walks all the discounts, redemptions and orders once, instead of fetching every order.
+    ///
+    /// # Arguments
+    /// - store_id: The ID of the store
+    ///
+    /// # Returns
+    /// - `anyhow::Result<StoreDiscountSummary, DiscountAnalyticsError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::discount_analytics::DiscountAnalytics;
+    ///
+    /// let analytics = DiscountAnalytics::build(lemonsqueezy);
+    /// let summary = analytics.store(1).await?;
+    ///
+    /// for discount in summary.discounts {
+    ///     println!("{}: {} redemptions", discount.code, discount.redemptions);
+    /// }
+    /// ```
+    pub async fn store(
+        &self,
+        store_id: i64,
+    ) -> anyhow::Result<StoreDiscountSummary, DiscountAnalyticsError> {
+        let discounts = self
+            .api
+            .get_all_pages::<DiscountResponse>(&format!(
+                "/v1/discounts?filter[store_id]={}",
+                store_id
+            ))
+            .await?;
+
+        // redemptions can't be filtered by store
+        let redemptions = self
+            .api
+            .get_all_pages::<DiscountRedemptionsResponse>("/v1/discount-redemptions")
+            .await?
+            .into_iter()
+            .map(|redemption| redemption.attributes)
+            .collect::<Vec<_>>();
+
+        let orders = self
+            .api
+            .get_all_pages::<OrderResponse>(&format!("/v1/orders?filter[store_id]={}", store_id))
+            .await?
+            .into_iter()
+            .filter_map(|order| Some((order.id.parse().ok()?, order.attributes)))
+            .collect::<HashMap<i64, _>>();
+
+        let mut summary = StoreDiscountSummary {
+            store_id,
+            ..Default::default()
+        };
+
+        for discount in discounts {
+            let Ok(discount_id) = discount.id.parse() else {
+                continue;
+            };
+
+            let discount = summarize(discount_id, &discount.attributes, &redemptions, &orders);
+
+            summary.redemptions += discount.redemptions;
+            summary.total_discounted_usd += discount.total_discounted_usd;
+            summary.revenue_usd += discount.revenue_usd;
+            summary.discounts.push(discount);
+        }
+
+        Ok(summary)
+    }
+}
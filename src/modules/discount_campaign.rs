@@ -0,0 +1,440 @@
+pub use crate::errors::{DiscountCampaignError, ValidationError};
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::header::HeaderMap;
+use serde_json::json;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+use crate::discount::{Discount, DiscountBuilder, DiscountResponse};
+use crate::errors::NetworkError;
+use crate::utils::Response;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscountCampaignConfig {
+    /// The number of codes to create
+    pub count: usize,
+    /// Template of the codes, every `#` is replaced with a random character of `alphabet`
+    pub pattern: String,
+    pub alphabet: String,
+    /// The maximum number of discounts created at the same time
+    pub concurrency: usize,
+    /// The API allows 300 requests per minute
+    pub requests_per_minute: u32,
+}
+
+impl Default for DiscountCampaignConfig {
+    fn default() -> Self {
+        Self {
+            count: 100,
+            pattern: "########".to_string(),
+            // without the look-alike 0, O, 1 and I
+            alphabet: "ABCDEFGHJKLMNPQRSTUVWXYZ23456789".to_string(),
+            concurrency: 10,
+            requests_per_minute: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatedDiscountCode {
+    pub code: String,
+    pub discount_id: String,
+    pub discount: DiscountResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedDiscountCode {
+    pub code: String,
+    pub error: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct DiscountCampaignReport {
+    pub created: Vec<CreatedDiscountCode>,
+    pub failed: Vec<FailedDiscountCode>,
+}
+
+impl DiscountCampaignReport {
+    /// Export the report as CSV, with a `code,status,discount_id,error` header
+    ///
+    /// # Example
+    /// ```
+    /// use lemonsqueezy::discount_campaign::{DiscountCampaignReport, FailedDiscountCode};
+    ///
+    /// let report = DiscountCampaignReport {
+    ///     created: vec![],
+    ///     failed: vec![FailedDiscountCode {
+    ///         code: "SUMMER7K2P".to_string(),
+    ///         error: "Status code: 422 Unprocessable Entity".to_string(),
+    ///     }],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     report.to_csv(),
+    ///     "code,status,discount_id,error\nSUMMER7K2P,failed,,Status code: 422 Unprocessable Entity\n"
+    /// );
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("code,status,discount_id,error\n");
+
+        let created = self
+            .created
+            .iter()
+            .map(|created| [created.code.as_str(), "created", &created.discount_id, ""]);
+        let failed = self
+            .failed
+            .iter()
+            .map(|failed| [failed.code.as_str(), "failed", "", &failed.error]);
+
+        for row in created.chain(failed) {
+            let row = row.map(csv_field);
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Generate `count` unique codes from a pattern, none of which is in `existing`
+///
+/// # Arguments
+/// - pattern: Template of the codes, every `#` is replaced with a random character of `alphabet`
+/// - alphabet: The characters to pick from
+/// - count: The number of codes to generate
+/// - existing: The codes already taken, in uppercase
+///
+/// # Returns
+/// - `Result<Vec<String>, ValidationError>` object, an error if the pattern can't produce enough codes
+///
+/// # Example
+/// ```
+/// use std::collections::HashSet;
+/// use lemonsqueezy::discount_campaign::generate_codes;
+///
+/// let existing = HashSet::from(["SUMMERA".to_string()]);
+/// let codes = generate_codes("SUMMER#", "AB", 1, &existing).unwrap();
+/// assert_eq!(codes, vec!["SUMMERB".to_string()]);
+///
+/// assert!(generate_codes("SUMMER#", "AB", 2, &existing).is_err());
+/// ```
+pub fn generate_codes(
+    pattern: &str,
+    alphabet: &str,
+    count: usize,
+    existing: &HashSet<String>,
+) -> Result<Vec<String>, ValidationError> {
+    let alphabet = alphabet.chars().collect::<HashSet<_>>();
+    let mut alphabet = alphabet.into_iter().collect::<Vec<_>>();
+    alphabet.sort_unstable();
+
+    if alphabet.is_empty() {
+        return Err(ValidationError::new("alphabet", "must not be empty"));
+    }
+
+    let slots = pattern.matches('#').count();
+
+    if slots == 0 {
+        return Err(ValidationError::new(
+            "pattern",
+            "must contain at least one #",
+        ));
+    }
+
+    let possible = u32::try_from(slots)
+        .ok()
+        .and_then(|slots| alphabet.len().checked_pow(slots))
+        .unwrap_or(usize::MAX);
+    let taken = existing
+        .iter()
+        .filter(|code| matches_pattern(code, pattern, &alphabet))
+        .count();
+
+    if possible.saturating_sub(taken) < count {
+        return Err(ValidationError::new(
+            "pattern",
+            format!(
+                "can only produce {} more codes",
+                possible.saturating_sub(taken)
+            ),
+        ));
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut codes = Vec::with_capacity(count);
+    let mut generated = HashSet::with_capacity(count);
+
+    while codes.len() < count {
+        let code = pattern
+            .chars()
+            .map(|c| match c {
+                '#' => alphabet[rng.gen_range(0..alphabet.len())],
+                c => c,
+            })
+            .collect::<String>();
+
+        if !existing.contains(&code) && generated.insert(code.clone()) {
+            codes.push(code);
+        }
+    }
+
+    Ok(codes)
+}
+
+fn matches_pattern(code: &str, pattern: &str, alphabet: &[char]) -> bool {
+    code.chars().count() == pattern.chars().count()
+        && code.chars().zip(pattern.chars()).all(|(c, p)| match p {
+            '#' => alphabet.contains(&c),
+            p => c == p,
+        })
+}
+
+/// Spaces the requests evenly to stay under the rate limit
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_minute: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(60) / requests_per_minute,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn wait(&self) {
+        let mut next = self.next.lock().await;
+        tokio::time::sleep_until(*next).await;
+        *next = Instant::now().max(*next) + self.interval;
+    }
+}
+
+/// Creates many unique discount codes sharing the same settings
+pub struct DiscountCampaign {
+    discount: Arc<Discount>,
+    template: DiscountBuilder,
+    config: DiscountCampaignConfig,
+}
+
+impl DiscountCampaign {
+    /// # Arguments
+    /// - discount: The discount module used to create the codes
+    /// - template: The settings of every discount, its code is replaced with each generated code
+    /// - config: The campaign config
+    pub fn build(
+        discount: Discount,
+        template: DiscountBuilder,
+        config: DiscountCampaignConfig,
+    ) -> Self {
+        Self {
+            discount: Arc::new(discount),
+            template,
+            config,
+        }
+    }
+
+    /// Generate the codes, skipping the existing codes of the store, and create the discounts
+    ///
+    /// The template is validated with a sample code first, an invalid template or pattern fails
+    /// the whole campaign with a single `ValidationError`. A code that fails to be created is
+    /// reported with the error of the API (e.g. `Status code: 422 Unprocessable Entity`) and not
+    /// retried.
+    ///
+    /// # Returns
+    /// - `anyhow::Result<DiscountCampaignReport, DiscountCampaignError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::discount::{AmountType, Discount};
+    /// use lemonsqueezy::discount_campaign::{DiscountCampaign, DiscountCampaignConfig};
+    ///
+    /// let template = Discount::builder(1)
+    ///     .name("Influencer")
+    ///     .amount(15, AmountType::Percent)
+    ///     .max_redemptions(1);
+    ///
+    /// let campaign = DiscountCampaign::build(
+    ///     Discount::build(lemonsqueezy),
+    ///     template,
+    ///     DiscountCampaignConfig {
+    ///         count: 500,
+    ///         pattern: "INFLU######".to_string(),
+    ///         ..Default::default()
+    ///     },
+    /// );
+    ///
+    /// let report = campaign.run().await?;
+    /// std::fs::write("codes.csv", report.to_csv())?;
+    /// ```
+    pub async fn run(&self) -> anyhow::Result<DiscountCampaignReport, DiscountCampaignError> {
+        if self.config.concurrency == 0 {
+            return Err(ValidationError::new("concurrency", "must be positive").into());
+        }
+
+        if self.config.requests_per_minute == 0 {
+            return Err(ValidationError::new("requests_per_minute", "must be positive").into());
+        }
+
+        // every code is built from the same template, pattern and alphabet, so building a sample
+        // code for every character of the alphabet catches an invalid campaign before any request
+        generate_codes(
+            &self.config.pattern,
+            &self.config.alphabet,
+            1,
+            &HashSet::new(),
+        )?;
+
+        for character in self.config.alphabet.chars() {
+            let sample = self.config.pattern.replace('#', &character.to_string());
+
+            self.template.clone().code(sample).build()?;
+        }
+
+        let existing = self
+            .discount
+            .api
+            .get_all_pages::<DiscountResponse>(&format!(
+                "/v1/discounts?filter[store_id]={}",
+                self.template.store_id
+            ))
+            .await?
+            .into_iter()
+            .map(|discount| discount.attributes.code.to_uppercase())
+            .collect::<HashSet<_>>();
+
+        let codes = generate_codes(
+            &self.config.pattern,
+            &self.config.alphabet,
+            self.config.count,
+            &existing,
+        )?;
+
+        let semaphore = Arc::new(Semaphore::new(self.config.concurrency));
+        let rate_limiter = Arc::new(RateLimiter::new(self.config.requests_per_minute));
+        let mut tasks = JoinSet::new();
+
+        for (index, code) in codes.into_iter().enumerate() {
+            let discount = self.discount.clone();
+            let template = self.template.clone();
+            let semaphore = semaphore.clone();
+            let rate_limiter = rate_limiter.clone();
+
+            tasks.spawn(async move {
+                let result = match template.code(code.clone()).build() {
+                    Ok(data) => {
+                        let _permit = semaphore.acquire_owned().await.unwrap();
+                        rate_limiter.wait().await;
+
+                        create(&discount, data)
+                            .await
+                            .map_err(|error| error.to_string())
+                    }
+                    Err(error) => Err(error.to_string()),
+                };
+
+                (index, code, result)
+            });
+        }
+
+        let mut results = Vec::new();
+
+        while let Some(result) = tasks.join_next().await {
+            results.push(result.expect("discount campaign task panicked"));
+        }
+
+        results.sort_by_key(|(index, _, _)| *index);
+
+        let mut report = DiscountCampaignReport::default();
+
+        for (_, code, result) in results {
+            match result {
+                Ok(response) => match response.get_data() {
+                    Some(data) => report.created.push(CreatedDiscountCode {
+                        code,
+                        discount_id: data.id.clone(),
+                        discount: data.attributes.clone(),
+                    }),
+                    None => report.failed.push(FailedDiscountCode {
+                        code,
+                        error: NetworkError::MissingData.to_string(),
+                    }),
+                },
+                Err(error) => report.failed.push(FailedDiscountCode { code, error }),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Create a discount, failing on an error status instead of returning the error body
+async fn create(
+    discount: &Discount,
+    data: crate::discount::CreateDiscount,
+) -> anyhow::Result<Response<DiscountResponse>, NetworkError> {
+    discount
+        .api
+        .post_with_headers("/v1/discounts", HeaderMap::new(), json!({ "data": data }))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discount::AmountType;
+
+    fn campaign(template: DiscountBuilder, alphabet: &str) -> DiscountCampaign {
+        DiscountCampaign::build(
+            Discount::build(crate::LemonSqueezy::new("key".to_string())),
+            template,
+            DiscountCampaignConfig {
+                count: 10,
+                pattern: "SUMMER####".to_string(),
+                alphabet: alphabet.to_string(),
+                concurrency: 5,
+                requests_per_minute: 300,
+            },
+        )
+    }
+
+    fn template() -> DiscountBuilder {
+        DiscountBuilder::new(1)
+            .name("Summer")
+            .amount(10, AmountType::Percent)
+    }
+
+    #[tokio::test]
+    async fn an_invalid_template_fails_once_before_any_request() {
+        let error = campaign(DiscountBuilder::new(1), "ABC").run().await;
+
+        match error {
+            Err(DiscountCampaignError::ValidationError(error)) => assert_eq!(error.field, "name"),
+            other => panic!("expected a validation error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn every_character_of_the_alphabet_is_validated() {
+        let error = campaign(template(), "ABc").run().await;
+
+        match error {
+            Err(DiscountCampaignError::ValidationError(error)) => assert_eq!(error.field, "code"),
+            other => panic!("expected a validation error, got {other:?}"),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::discount::AmountType;
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DiscountRedemptionsResponse {
@@ -9,10 +10,10 @@ pub struct DiscountRedemptionsResponse {
     pub discount_name: String,
     pub discount_code: String,
     pub discount_amount: i64,
-    pub discount_amount_type: String,
+    pub discount_amount_type: AmountType,
     pub amount: i64,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
 }
 
 pub struct DiscountRedemptionsFilters {
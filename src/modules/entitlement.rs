@@ -0,0 +1,272 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::subscriptions::{PauseMode, SubscriptionResponse, SubscriptionStatus};
+use crate::utils::ToDateTime;
+
+/// Whether a `past_due` subscription (a renewal payment failed and is being retried) keeps access
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PastDuePolicy {
+    /// Keep access while the payment is retried, LemonSqueezy moves the subscription to
+    /// `unpaid` once all the retries failed
+    #[default]
+    Allow,
+    /// Revoke access as soon as a renewal payment fails
+    Deny,
+    /// Keep access for the given duration after the failed renewal (`renews_at`)
+    GracePeriod(Duration),
+}
+
+/// Decides whether a customer should have access to the product from their subscription
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entitlement {
+    pub past_due: PastDuePolicy,
+}
+
+impl Entitlement {
+    pub fn new(past_due: PastDuePolicy) -> Self {
+        Self { past_due }
+    }
+
+    /// Check whether the subscription grants access at `now`
+    ///
+    /// - `on_trial`: until `trial_ends_at`
+    /// - `active`: always
+    /// - `past_due`: according to the `PastDuePolicy`
+    /// - `paused`: only if paused with `PauseMode::Free`
+    /// - `cancelled`: until `ends_at` (the end of the paid period)
+    /// - `expired`, `unpaid` and unknown statuses: never
+    ///
+    /// # Example
+    /// ```
+    /// use chrono::{Duration, TimeZone, Utc};
+    /// use lemonsqueezy::entitlement::{Entitlement, PastDuePolicy};
+    /// use lemonsqueezy::subscriptions::{SubscriptionResponse, SubscriptionStatus};
+    ///
+    /// let now = Utc.with_ymd_and_hms(2023, 6, 15, 0, 0, 0).unwrap();
+    /// let entitlement = Entitlement::new(PastDuePolicy::GracePeriod(Duration::days(3)));
+    ///
+    /// let cancelled = SubscriptionResponse {
+    ///     status: SubscriptionStatus::Cancelled,
+    ///     ends_at: "2023-06-20T00:00:00.000000Z".parse().ok(),
+    ///     ..Default::default()
+    /// };
+    /// assert!(entitlement.has_access(&cancelled, now));
+    /// assert!(!entitlement.has_access(&cancelled, now + Duration::days(10)));
+    ///
+    /// let past_due = SubscriptionResponse {
+    ///     status: SubscriptionStatus::PastDue,
+    ///     renews_at: "2023-06-14T00:00:00.000000Z".parse().unwrap(),
+    ///     ..Default::default()
+    /// };
+    /// assert!(entitlement.has_access(&past_due, now));
+    /// assert!(!entitlement.has_access(&past_due, now + Duration::days(3)));
+    ///
+    /// let expired = SubscriptionResponse {
+    ///     status: SubscriptionStatus::Expired,
+    ///     ..Default::default()
+    /// };
+    /// assert!(!entitlement.has_access(&expired, now));
+    /// ```
+    pub fn has_access(&self, subscription: &SubscriptionResponse, now: DateTime<Utc>) -> bool {
+        match subscription.status {
+            SubscriptionStatus::OnTrial => subscription
+                .trial_ends_at
+                .to_datetime()
+                .is_none_or(|trial_ends_at| now < trial_ends_at),
+            SubscriptionStatus::Active => true,
+            SubscriptionStatus::PastDue => match self.past_due {
+                PastDuePolicy::Allow => true,
+                PastDuePolicy::Deny => false,
+                PastDuePolicy::GracePeriod(grace) => subscription
+                    .renews_at
+                    .to_datetime()
+                    .is_some_and(|renews_at| now < renews_at + grace),
+            },
+            SubscriptionStatus::Paused => subscription
+                .pause
+                .as_ref()
+                .is_some_and(|pause| pause.mode == PauseMode::Free),
+            SubscriptionStatus::Cancelled => subscription
+                .ends_at
+                .to_datetime()
+                .or_else(|| subscription.renews_at.to_datetime())
+                .is_some_and(|ends_at| now < ends_at),
+            SubscriptionStatus::Unpaid
+            | SubscriptionStatus::Expired
+            | SubscriptionStatus::Unknown(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subscriptions::SubscriptionPause;
+
+    fn now() -> DateTime<Utc> {
+        "2023-06-15T00:00:00Z".parse().unwrap()
+    }
+
+    fn subscription(status: SubscriptionStatus) -> SubscriptionResponse {
+        SubscriptionResponse {
+            status,
+            renews_at: "2023-07-01T00:00:00.000000Z".parse().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn past_due() -> SubscriptionResponse {
+        SubscriptionResponse {
+            renews_at: "2023-06-14T00:00:00.000000Z".parse().unwrap(),
+            ..subscription(SubscriptionStatus::PastDue)
+        }
+    }
+
+    fn paused(mode: PauseMode) -> SubscriptionResponse {
+        SubscriptionResponse {
+            pause: Some(SubscriptionPause {
+                mode,
+                resumes_at: None,
+            }),
+            ..subscription(SubscriptionStatus::Paused)
+        }
+    }
+
+    #[test]
+    fn active_has_access() {
+        assert!(Entitlement::default().has_access(&subscription(SubscriptionStatus::Active), now()));
+    }
+
+    #[test]
+    fn on_trial_has_access_until_the_trial_ends() {
+        let on_trial = SubscriptionResponse {
+            trial_ends_at: "2023-06-20T00:00:00.000000Z".parse().ok(),
+            ..subscription(SubscriptionStatus::OnTrial)
+        };
+        let entitlement = Entitlement::default();
+
+        assert!(entitlement.has_access(&on_trial, now()));
+        assert!(!entitlement.has_access(&on_trial, now() + Duration::days(5)));
+    }
+
+    #[test]
+    fn on_trial_without_trial_end_has_access() {
+        let on_trial = SubscriptionResponse {
+            trial_ends_at: None,
+            ..subscription(SubscriptionStatus::OnTrial)
+        };
+
+        assert!(Entitlement::default().has_access(&on_trial, now()));
+    }
+
+    #[test]
+    fn paused_free_has_access() {
+        assert!(Entitlement::default().has_access(&paused(PauseMode::Free), now()));
+    }
+
+    #[test]
+    fn paused_void_has_no_access() {
+        assert!(!Entitlement::default().has_access(&paused(PauseMode::Void), now()));
+    }
+
+    #[test]
+    fn paused_without_pause_has_no_access() {
+        let paused = SubscriptionResponse {
+            pause: None,
+            ..subscription(SubscriptionStatus::Paused)
+        };
+
+        assert!(!Entitlement::default().has_access(&paused, now()));
+    }
+
+    #[test]
+    fn past_due_allow_has_access() {
+        assert!(Entitlement::new(PastDuePolicy::Allow).has_access(&past_due(), now()));
+    }
+
+    #[test]
+    fn past_due_deny_has_no_access() {
+        assert!(!Entitlement::new(PastDuePolicy::Deny).has_access(&past_due(), now()));
+    }
+
+    #[test]
+    fn past_due_grace_period_has_access_until_it_ends() {
+        let entitlement = Entitlement::new(PastDuePolicy::GracePeriod(Duration::days(3)));
+
+        assert!(entitlement.has_access(&past_due(), now()));
+        assert!(entitlement.has_access(&past_due(), now() + Duration::hours(47)));
+        assert!(!entitlement.has_access(&past_due(), now() + Duration::days(2)));
+    }
+
+    #[test]
+    fn cancelled_has_access_until_it_ends() {
+        let cancelled = SubscriptionResponse {
+            ends_at: "2023-06-20T00:00:00.000000Z".parse().ok(),
+            ..subscription(SubscriptionStatus::Cancelled)
+        };
+        let entitlement = Entitlement::default();
+
+        assert!(entitlement.has_access(&cancelled, now()));
+        assert!(!entitlement.has_access(&cancelled, now() + Duration::days(5)));
+    }
+
+    #[test]
+    fn cancelled_without_end_falls_back_to_the_renewal_date() {
+        let cancelled = SubscriptionResponse {
+            ends_at: None,
+            ..subscription(SubscriptionStatus::Cancelled)
+        };
+        let entitlement = Entitlement::default();
+
+        assert!(entitlement.has_access(&cancelled, now()));
+        assert!(!entitlement.has_access(&cancelled, now() + Duration::days(16)));
+    }
+
+    #[test]
+    fn unpaid_has_no_access() {
+        for policy in [
+            PastDuePolicy::Allow,
+            PastDuePolicy::Deny,
+            PastDuePolicy::GracePeriod(Duration::days(30)),
+        ] {
+            let entitlement = Entitlement::new(policy);
+
+            assert!(!entitlement.has_access(&subscription(SubscriptionStatus::Unpaid), now()));
+        }
+    }
+
+    #[test]
+    fn expired_has_no_access() {
+        assert!(
+            !Entitlement::default().has_access(&subscription(SubscriptionStatus::Expired), now())
+        );
+    }
+
+    #[test]
+    fn unknown_status_has_no_access() {
+        let unknown = subscription(SubscriptionStatus::Unknown("frozen".to_string()));
+
+        assert!(!Entitlement::default().has_access(&unknown, now()));
+    }
+
+    #[test]
+    fn statuses_deserialize_from_the_api() {
+        let fixtures = [
+            ("on_trial", SubscriptionStatus::OnTrial),
+            ("active", SubscriptionStatus::Active),
+            ("paused", SubscriptionStatus::Paused),
+            ("past_due", SubscriptionStatus::PastDue),
+            ("unpaid", SubscriptionStatus::Unpaid),
+            ("cancelled", SubscriptionStatus::Cancelled),
+            ("expired", SubscriptionStatus::Expired),
+            ("frozen", SubscriptionStatus::Unknown("frozen".to_string())),
+        ];
+
+        for (json, status) in fixtures {
+            assert_eq!(
+                serde_json::from_value::<SubscriptionStatus>(serde_json::json!(json)).unwrap(),
+                status
+            );
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FileResponse {
@@ -15,9 +15,9 @@ pub struct FileResponse {
     pub sort: Option<i64>,
     pub status: Option<String>,
     #[serde(rename = "createdAt")]
-    pub created_at: String,
+    pub created_at: Timestamp,
     #[serde(rename = "updatedAt")]
-    pub updated_at: String,
+    pub updated_at: Timestamp,
 }
 
 pub struct FileFilters {
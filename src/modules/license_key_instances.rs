@@ -1,14 +1,18 @@
+pub use crate::errors::{LicenseKeyInstancesError, ValidationError};
+
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::license_keys::{LicenseDeactivationResponse, LicenseKey, LicenseKeyResponse};
+use crate::utils::{Response, ResponseData, Timestamp, ToDateTime, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LicenseKeyInstancesResponse {
     pub license_key_id: i64,
     pub identifier: String,
     pub name: String,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
 }
 
 pub struct LicenseKeyInstancesFilters {
@@ -91,4 +95,282 @@ impl LicenseKeyInstances {
 
         Ok(response)
     }
+
+    /// Retrieve all the instances of a license key, by the key itself
+    ///
+    /// # Arguments
+    /// - license_key: The license key to retrieve the instances of
+    ///
+    /// # Returns
+    /// - `anyhow::Result<Vec<ResponseData<LicenseKeyInstancesResponse>>, crate::errors::NetworkError>` object, empty if the license key does not exist
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::license_key_instances::LicenseKeyInstances;
+    ///
+    /// let license_key_instances = LicenseKeyInstances::build(lemonsqueezy);
+    /// let instances = license_key_instances.get_all_for_key("38b1460a-5104-4067-a91d-77b872934d51").await.unwrap();
+    /// ```
+    pub async fn get_all_for_key(
+        &self,
+        license_key: &str,
+    ) -> anyhow::Result<Vec<ResponseData<LicenseKeyInstancesResponse>>, crate::errors::NetworkError>
+    {
+        // the validation endpoint is the only way to resolve a key to its ID
+        let validation = LicenseKey::build(self.api.clone())
+            .validate(license_key, None)
+            .await?;
+
+        let license_key_id = match validation.license_key {
+            Some(license_key) => license_key.id,
+            None => return Ok(Vec::new()),
+        };
+
+        let response = self
+            .api
+            .get_all_pages(&format!(
+                "/v1/license-key-instances?filter[license_key_id]={}",
+                license_key_id
+            ))
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Deactivate the instances of a license key created more than `days` days ago
+    ///
+    /// # Arguments
+    /// - license_key: The license key to deactivate the stale instances of
+    /// - days: The age in days after which an instance is considered stale
+    ///
+    /// # Returns
+    /// - `anyhow::Result<Vec<LicenseDeactivationResponse>, LicenseKeyInstancesError>` object, with one response per deactivated instance, an error if `days` is negative or too large
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::license_key_instances::LicenseKeyInstances;
+    ///
+    /// let license_key_instances = LicenseKeyInstances::build(lemonsqueezy);
+    /// let deactivated = license_key_instances.deactivate_stale("38b1460a-5104-4067-a91d-77b872934d51", 30).await.unwrap();
+    /// ```
+    pub async fn deactivate_stale(
+        &self,
+        license_key: &str,
+        days: i64,
+    ) -> anyhow::Result<Vec<LicenseDeactivationResponse>, LicenseKeyInstancesError> {
+        let cutoff = stale_cutoff(Utc::now(), days)?;
+
+        let stale = self
+            .get_all_for_key(license_key)
+            .await?
+            .into_iter()
+            .filter(|instance| {
+                instance
+                    .attributes
+                    .created_at
+                    .to_datetime()
+                    .is_some_and(|created_at| created_at < cutoff)
+            })
+            .map(|instance| (license_key.to_string(), instance))
+            .collect::<Vec<_>>();
+
+        Ok(self.deactivate_all(stale).await?)
+    }
+
+    /// Deactivate the oldest instances of a license key until at most `max_instances` remain active
+    ///
+    /// # Arguments
+    /// - license_key: The license key to enforce the limit on
+    /// - max_instances: The number of (most recently created) instances to keep active
+    ///
+    /// # Returns
+    /// - `anyhow::Result<Vec<LicenseDeactivationResponse>, crate::errors::NetworkError>` object, with one response per deactivated instance
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::license_key_instances::LicenseKeyInstances;
+    ///
+    /// let license_key_instances = LicenseKeyInstances::build(lemonsqueezy);
+    /// let deactivated = license_key_instances.enforce_instance_limit("38b1460a-5104-4067-a91d-77b872934d51", 2).await.unwrap();
+    /// ```
+    pub async fn enforce_instance_limit(
+        &self,
+        license_key: &str,
+        max_instances: usize,
+    ) -> anyhow::Result<Vec<LicenseDeactivationResponse>, crate::errors::NetworkError> {
+        let instances = self
+            .get_all_for_key(license_key)
+            .await?
+            .into_iter()
+            .map(|instance| (license_key.to_string(), instance))
+            .collect();
+
+        self.deactivate_all(oldest_over_limit(instances, max_instances))
+            .await
+    }
+
+    /// Deactivate every instance of the license keys of a customer except the most recently
+    /// created one, so that each customer has a single active instance across all their keys
+    ///
+    /// Disabled license keys are ignored, their instances can't be deactivated.
+    ///
+    /// # Arguments
+    /// - store_id: The ID of the store of the license keys
+    /// - customer_id: The ID of the customer to enforce a single active instance on
+    ///
+    /// # Returns
+    /// - `anyhow::Result<Vec<LicenseDeactivationResponse>, crate::errors::NetworkError>` object, with one response per deactivated instance
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::license_key_instances::LicenseKeyInstances;
+    ///
+    /// let license_key_instances = LicenseKeyInstances::build(lemonsqueezy);
+    /// let deactivated = license_key_instances.enforce_single_instance_per_customer(1, 42).await.unwrap();
+    /// ```
+    pub async fn enforce_single_instance_per_customer(
+        &self,
+        store_id: i64,
+        customer_id: i64,
+    ) -> anyhow::Result<Vec<LicenseDeactivationResponse>, crate::errors::NetworkError> {
+        // license keys can't be filtered by customer
+        let license_keys = self
+            .api
+            .get_all_pages::<LicenseKeyResponse>(&format!(
+                "/v1/license-keys?filter[store_id]={}",
+                store_id
+            ))
+            .await?
+            .into_iter()
+            .filter(|license_key| {
+                license_key.attributes.customer_id == customer_id
+                    && !license_key.attributes.disabled
+            });
+
+        let mut instances = Vec::new();
+
+        for license_key in license_keys {
+            let key_instances = self
+                .api
+                .get_all_pages::<LicenseKeyInstancesResponse>(&format!(
+                    "/v1/license-key-instances?filter[license_key_id]={}",
+                    license_key.id
+                ))
+                .await?;
+
+            instances.extend(
+                key_instances
+                    .into_iter()
+                    .map(|instance| (license_key.attributes.key.clone(), instance)),
+            );
+        }
+
+        self.deactivate_all(oldest_over_limit(instances, 1)).await
+    }
+
+    async fn deactivate_all(
+        &self,
+        instances: Vec<(String, ResponseData<LicenseKeyInstancesResponse>)>,
+    ) -> anyhow::Result<Vec<LicenseDeactivationResponse>, crate::errors::NetworkError> {
+        let license_keys = LicenseKey::build(self.api.clone());
+
+        let mut responses = Vec::with_capacity(instances.len());
+
+        for (license_key, instance) in instances {
+            let response = license_keys
+                .deactivate(&license_key, &instance.attributes.identifier)
+                .await?;
+
+            responses.push(response);
+        }
+
+        Ok(responses)
+    }
+}
+
+/// The instant before which an instance created is stale
+fn stale_cutoff(now: DateTime<Utc>, days: i64) -> Result<DateTime<Utc>, ValidationError> {
+    if days < 0 {
+        return Err(ValidationError::new("days", "can't be negative"));
+    }
+
+    Duration::try_days(days)
+        .and_then(|age| now.checked_sub_signed(age))
+        .ok_or_else(|| ValidationError::new("days", format!("{} is out of range", days)))
+}
+
+/// The instances to deactivate to keep the `max_instances` most recently created ones
+///
+/// Instances with an unreadable `created_at` are considered the oldest.
+fn oldest_over_limit<K>(
+    mut instances: Vec<(K, ResponseData<LicenseKeyInstancesResponse>)>,
+    max_instances: usize,
+) -> Vec<(K, ResponseData<LicenseKeyInstancesResponse>)> {
+    if instances.len() <= max_instances {
+        return Vec::new();
+    }
+
+    // oldest first
+    instances.sort_by_key(|(_, instance)| instance.attributes.created_at.to_datetime());
+    instances.truncate(instances.len() - max_instances);
+
+    instances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(identifier: &str, created_at: &str) -> ResponseData<LicenseKeyInstancesResponse> {
+        ResponseData {
+            r#type: "license-key-instances".to_string(),
+            id: identifier.to_string(),
+            relationships: None,
+            attributes: LicenseKeyInstancesResponse {
+                identifier: identifier.to_string(),
+                created_at: created_at.parse().unwrap(),
+                ..Default::default()
+            },
+            links: None,
+        }
+    }
+
+    #[test]
+    fn stale_cutoff_rejects_negative_and_out_of_range_days() {
+        let now = "2024-03-31T00:00:00Z".parse().unwrap();
+
+        assert_eq!(
+            stale_cutoff(now, 30).unwrap(),
+            "2024-03-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(stale_cutoff(now, -1).unwrap_err().field, "days");
+        assert_eq!(stale_cutoff(now, i64::MAX).unwrap_err().field, "days");
+        assert_eq!(stale_cutoff(now, 1_000_000_000).unwrap_err().field, "days");
+    }
+
+    #[test]
+    fn oldest_over_limit_keeps_the_most_recent_instances_across_keys() {
+        let instances = vec![
+            ("key-a", instance("new", "2024-03-03T00:00:00Z")),
+            ("key-b", instance("old", "2024-03-01T00:00:00Z")),
+            ("key-a", instance("mid", "2024-03-02T00:00:00Z")),
+        ];
+
+        let deactivated = oldest_over_limit(instances, 1)
+            .into_iter()
+            .map(|(key, instance)| (key, instance.attributes.identifier))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            deactivated,
+            vec![("key-b", "old".to_string()), ("key-a", "mid".to_string())]
+        );
+    }
+
+    #[test]
+    fn oldest_over_limit_is_empty_under_the_limit() {
+        let instances = vec![("key-a", instance("only", "2024-03-01T00:00:00Z"))];
+
+        assert!(oldest_over_limit(instances, 1).is_empty());
+    }
 }
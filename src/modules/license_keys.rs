@@ -1,7 +1,6 @@
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 use reqwest::header::{HeaderMap, HeaderValue};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LicenseKeyResponse {
@@ -17,11 +16,20 @@ pub struct LicenseKeyResponse {
     pub activation_limit: Option<i64>,
     pub instances_count: i64,
     pub disabled: bool,
-    pub status: String,
+    pub status: LicenseKeyStatus,
     pub status_formatted: String,
-    pub expires_at: Option<Value>,
-    pub created_at: String,
-    pub updated_at: String,
+    pub expires_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+string_enum! {
+    pub enum LicenseKeyStatus {
+        Inactive => "inactive",
+        Active => "active",
+        Expired => "expired",
+        Disabled => "disabled",
+    }
 }
 
 pub struct LicenseKeyFilters {
@@ -49,18 +57,18 @@ pub struct LicenseActivationMeta {
 pub struct LicenseActivationInstance {
     pub id: String,
     pub name: String,
-    pub created_at: String,
+    pub created_at: Timestamp,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LicenseActivationKey {
     pub id: i64,
-    pub status: String,
+    pub status: LicenseKeyStatus,
     pub key: String,
     pub activation_limit: Option<i64>,
     pub activation_usage: i64,
-    pub created_at: String,
-    pub expires_at: Option<String>,
+    pub created_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,302 @@
+//! Ed25519 signed license tokens, issued from a validated license key and verifiable offline.
+//! A token is `base64url(claims JSON).base64url(signature)`.
+
+pub use crate::errors::LicenseTokenError;
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Signature, Signer, Verifier};
+use serde::{Deserialize, Serialize};
+
+use crate::license_keys::{LicenseKeyStatus, LicenseValidationResponse};
+use crate::utils::ToDateTime;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LicenseTokenClaims {
+    pub license_key_id: i64,
+    pub store_id: i64,
+    pub product_id: i64,
+    pub variant_id: i64,
+    pub instance_id: Option<String>,
+    /// Unix timestamp (seconds) at which the token was issued
+    pub issued_at: i64,
+    /// Unix timestamp (seconds) after which the token is no longer valid
+    pub expires_at: i64,
+}
+
+pub struct LicenseTokenIssuer {
+    signing_key: SigningKey,
+    valid_for: Duration,
+}
+
+impl LicenseTokenIssuer {
+    /// Build a token issuer
+    ///
+    /// # Arguments
+    /// - signing_key: The Ed25519 key the tokens are signed with
+    /// - valid_for: How long an issued token stays valid, capped at the license key expiry
+    pub fn build(signing_key: SigningKey, valid_for: Duration) -> Self {
+        Self {
+            signing_key,
+            valid_for,
+        }
+    }
+
+    /// Issue a token from the response of `LicenseKey::validate`
+    ///
+    /// No token is issued for a license key that is not active or has already expired.
+    ///
+    /// # Arguments
+    /// - validation: The validation response of the license key
+    ///
+    /// # Returns
+    /// - `Result<String, LicenseTokenError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use chrono::Duration;
+    /// use lemonsqueezy::license_keys::LicenseKey;
+    /// use lemonsqueezy::license_token::{LicenseTokenIssuer, SigningKey};
+    ///
+    /// let validation = LicenseKey::build(lemonsqueezy)
+    ///     .validate("38b1460a-5104-4067-a91d-77b872934d51", Some("instance-id"))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// let issuer = LicenseTokenIssuer::build(SigningKey::from_bytes(&secret), Duration::days(30));
+    /// let token = issuer.issue(&validation).unwrap();
+    /// ```
+    pub fn issue(
+        &self,
+        validation: &LicenseValidationResponse,
+    ) -> Result<String, LicenseTokenError> {
+        self.issue_at(validation, Utc::now())
+    }
+
+    /// Issue a token from the response of `LicenseKey::validate`, as if the current time was `now`
+    pub fn issue_at(
+        &self,
+        validation: &LicenseValidationResponse,
+        now: DateTime<Utc>,
+    ) -> Result<String, LicenseTokenError> {
+        if !validation.valid {
+            return Err(LicenseTokenError::InvalidLicense(
+                validation
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| "validation failed".to_string()),
+            ));
+        }
+
+        let (license_key, meta) = match (&validation.license_key, &validation.meta) {
+            (Some(license_key), Some(meta)) => (license_key, meta),
+            _ => {
+                return Err(LicenseTokenError::InvalidLicense(
+                    "missing license key or meta".to_string(),
+                ))
+            }
+        };
+
+        // the status is only refreshed by Lemon Squeezy periodically, the expiry is checked too
+        if license_key.status != LicenseKeyStatus::Active {
+            return Err(LicenseTokenError::InactiveLicense(
+                license_key.status.clone(),
+            ));
+        }
+
+        let mut expires_at = now + self.valid_for;
+
+        if let Some(license_expires_at) = license_key.expires_at.to_datetime() {
+            if license_expires_at <= now {
+                return Err(LicenseTokenError::LicenseExpired);
+            }
+
+            expires_at = expires_at.min(license_expires_at);
+        }
+
+        let claims = LicenseTokenClaims {
+            license_key_id: license_key.id,
+            store_id: meta.store_id,
+            product_id: meta.product_id,
+            variant_id: meta.variant_id,
+            instance_id: validation.instance.as_ref().map(|i| i.id.clone()),
+            issued_at: now.timestamp(),
+            expires_at: expires_at.timestamp(),
+        };
+
+        let payload = serde_json::to_vec(&claims)?;
+        let signature = self.signing_key.sign(&payload);
+
+        Ok(format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(payload),
+            URL_SAFE_NO_PAD.encode(signature.to_bytes())
+        ))
+    }
+}
+
+pub struct LicenseTokenVerifier {
+    verifying_key: VerifyingKey,
+}
+
+impl LicenseTokenVerifier {
+    /// Build a token verifier
+    ///
+    /// # Arguments
+    /// - verifying_key: The public half of the key the tokens were signed with
+    pub fn build(verifying_key: VerifyingKey) -> Self {
+        Self { verifying_key }
+    }
+
+    /// Verify a token offline and return its claims
+    ///
+    /// # Arguments
+    /// - token: The token issued by `LicenseTokenIssuer::issue`
+    ///
+    /// # Returns
+    /// - `Result<LicenseTokenClaims, LicenseTokenError>` object
+    ///
+    /// # Example
+    /// ```
+    /// use chrono::Duration;
+    /// use lemonsqueezy::license_keys::{
+    ///     LicenseActivationKey, LicenseActivationMeta, LicenseKeyStatus, LicenseValidationResponse,
+    /// };
+    /// use lemonsqueezy::license_token::{LicenseTokenIssuer, LicenseTokenVerifier, SigningKey};
+    ///
+    /// let signing_key = SigningKey::from_bytes(&[7; 32]);
+    /// let verifier = LicenseTokenVerifier::build(signing_key.verifying_key());
+    /// let issuer = LicenseTokenIssuer::build(signing_key, Duration::days(30));
+    ///
+    /// let validation = LicenseValidationResponse {
+    ///     valid: true,
+    ///     license_key: Some(LicenseActivationKey {
+    ///         id: 1,
+    ///         status: LicenseKeyStatus::Active,
+    ///         ..Default::default()
+    ///     }),
+    ///     meta: Some(LicenseActivationMeta { product_id: 2, ..Default::default() }),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let token = issuer.issue(&validation).unwrap();
+    /// let claims = verifier.verify(&token).unwrap();
+    ///
+    /// assert_eq!(claims.license_key_id, 1);
+    /// assert_eq!(claims.product_id, 2);
+    /// assert!(verifier.verify(&token.replace('.', ".x")).is_err());
+    /// ```
+    pub fn verify(&self, token: &str) -> Result<LicenseTokenClaims, LicenseTokenError> {
+        self.verify_at(token, Utc::now())
+    }
+
+    /// Verify a token offline and return its claims, as if the current time was `now`
+    pub fn verify_at(
+        &self,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Result<LicenseTokenClaims, LicenseTokenError> {
+        let (payload, signature) = token.split_once('.').ok_or(LicenseTokenError::Malformed)?;
+
+        let payload = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|_| LicenseTokenError::Malformed)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .ok()
+            .and_then(|signature| Signature::from_slice(&signature).ok())
+            .ok_or(LicenseTokenError::Malformed)?;
+
+        self.verifying_key
+            .verify(&payload, &signature)
+            .map_err(|_| LicenseTokenError::InvalidSignature)?;
+
+        let claims: LicenseTokenClaims =
+            serde_json::from_slice(&payload).map_err(|_| LicenseTokenError::Malformed)?;
+
+        if claims.expires_at <= now.timestamp() {
+            return Err(LicenseTokenError::Expired);
+        }
+
+        Ok(claims)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::license_keys::{LicenseActivationKey, LicenseActivationMeta};
+
+    fn issuer() -> LicenseTokenIssuer {
+        LicenseTokenIssuer::build(SigningKey::from_bytes(&[7; 32]), Duration::days(30))
+    }
+
+    fn validation(status: LicenseKeyStatus, expires_at: Option<&str>) -> LicenseValidationResponse {
+        LicenseValidationResponse {
+            valid: true,
+            license_key: Some(LicenseActivationKey {
+                id: 1,
+                status,
+                expires_at: expires_at.map(|expires_at| expires_at.parse().unwrap()),
+                ..Default::default()
+            }),
+            meta: Some(LicenseActivationMeta::default()),
+            ..Default::default()
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2024-03-01T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn issues_a_token_capped_at_the_license_expiry() {
+        let token = issuer()
+            .issue_at(
+                &validation(LicenseKeyStatus::Active, Some("2024-03-10T00:00:00Z")),
+                now(),
+            )
+            .unwrap();
+
+        let claims = LicenseTokenVerifier::build(SigningKey::from_bytes(&[7; 32]).verifying_key())
+            .verify_at(&token, now())
+            .unwrap();
+
+        assert_eq!(
+            claims.expires_at,
+            "2024-03-10T00:00:00Z"
+                .parse::<DateTime<Utc>>()
+                .unwrap()
+                .timestamp()
+        );
+    }
+
+    #[test]
+    fn refuses_an_expired_license_key() {
+        let result = issuer().issue_at(
+            &validation(LicenseKeyStatus::Active, Some("2024-02-01T00:00:00Z")),
+            now(),
+        );
+
+        assert!(matches!(result, Err(LicenseTokenError::LicenseExpired)));
+    }
+
+    #[test]
+    fn refuses_a_license_key_that_is_not_active() {
+        for status in [
+            LicenseKeyStatus::Disabled,
+            LicenseKeyStatus::Expired,
+            LicenseKeyStatus::Inactive,
+            LicenseKeyStatus::Unknown("suspended".to_string()),
+        ] {
+            let result = issuer().issue_at(&validation(status.clone(), None), now());
+
+            assert!(
+                matches!(&result, Err(LicenseTokenError::InactiveLicense(s)) if *s == status),
+                "{:?}",
+                result
+            );
+        }
+    }
+}
@@ -1,19 +1,31 @@
+pub mod cache;
+pub mod catalog;
 pub mod checkout;
+pub mod checkout_url;
 pub mod customer;
 pub mod discount;
+pub mod discount_analytics;
+pub mod discount_campaign;
 pub mod discount_redemptions;
+pub mod entitlement;
 pub mod files;
 pub mod license_key_instances;
 pub mod license_keys;
+pub mod license_token;
+pub mod money;
 pub mod order_items;
 pub mod orders;
 pub mod prices;
+pub mod pricing;
 pub mod products;
+pub mod proration;
 pub mod store;
 pub mod subscription_invoice;
 pub mod subscription_items;
+pub mod subscription_mirror;
 pub mod subscriptions;
 pub mod usage_records;
+pub mod usage_reporter;
 pub mod user;
 pub mod variants;
 pub mod webhook;
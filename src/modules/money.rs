@@ -0,0 +1,130 @@
+pub use crate::errors::MoneyError;
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Currencies without a minor unit, their amounts are not in cents
+const ZERO_DECIMAL_CURRENCIES: [&str; 16] = [
+    "BIF", "CLP", "DJF", "GNF", "JPY", "KMF", "KRW", "MGA", "PYG", "RWF", "UGX", "VND", "VUV",
+    "XAF", "XOF", "XPF",
+];
+
+/// An amount in the minor unit of a currency, e.g. cents for USD
+///
+/// # Example
+/// ```
+/// use lemonsqueezy::money::{Money, MoneyError};
+///
+/// let price = Money::new(1999, "usd");
+/// assert_eq!(price.to_string(), "19.99 USD");
+/// assert_eq!(Money::new(1200, "JPY").to_string(), "1200 JPY");
+///
+/// let total = price.checked_mul(3).unwrap().checked_sub(&Money::usd(997)).unwrap();
+/// assert_eq!(total, Money::usd(5000));
+///
+/// assert_eq!(
+///     price.checked_add(&Money::new(100, "EUR")),
+///     Err(MoneyError::CurrencyMismatch("USD".to_string(), "EUR".to_string()))
+/// );
+/// assert_eq!(Money::usd(i64::MAX).checked_add(&price), Err(MoneyError::Overflow));
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    pub amount_minor: i64,
+    /// ISO 4217 code, in uppercase
+    pub currency: String,
+}
+
+impl Money {
+    pub fn new(amount_minor: i64, currency: impl Into<String>) -> Self {
+        Self {
+            amount_minor,
+            currency: currency.into().to_uppercase(),
+        }
+    }
+
+    pub fn usd(amount_minor: i64) -> Self {
+        Self::new(amount_minor, "USD")
+    }
+
+    /// The number of digits of the minor unit, 0 for zero-decimal currencies like JPY
+    pub fn decimals(&self) -> u32 {
+        if ZERO_DECIMAL_CURRENCIES.contains(&self.currency.as_str()) {
+            0
+        } else {
+            2
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount_minor == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount_minor < 0
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+
+        self.amount_minor
+            .checked_add(other.amount_minor)
+            .map(|amount_minor| Money::new(amount_minor, &self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+
+        self.amount_minor
+            .checked_sub(other.amount_minor)
+            .map(|amount_minor| Money::new(amount_minor, &self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    pub fn checked_mul(&self, quantity: i64) -> Result<Money, MoneyError> {
+        self.amount_minor
+            .checked_mul(quantity)
+            .map(|amount_minor| Money::new(amount_minor, &self.currency))
+            .ok_or(MoneyError::Overflow)
+    }
+
+    /// The amount in the major unit, e.g. `19.99` for 1999 cents
+    pub fn format_amount(&self) -> String {
+        let decimals = self.decimals();
+        let sign = if self.is_negative() { "-" } else { "" };
+        let amount = self.amount_minor.unsigned_abs();
+
+        if decimals == 0 {
+            return format!("{}{}", sign, amount);
+        }
+
+        let unit = 10u64.pow(decimals);
+
+        format!(
+            "{}{}.{:0width$}",
+            sign,
+            amount / unit,
+            amount % unit,
+            width = decimals as usize
+        )
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(MoneyError::CurrencyMismatch(
+                self.currency.clone(),
+                other.currency.clone(),
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.format_amount(), self.currency)
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderItemResponse {
@@ -16,9 +16,9 @@ pub struct OrderItemResponse {
     pub variant_name: String,
     pub price: i64,
     #[serde(rename = "created_at")]
-    pub created_at: String,
+    pub created_at: Timestamp,
     #[serde(rename = "updated_at")]
-    pub updated_at: String,
+    pub updated_at: Timestamp,
 }
 
 pub struct OrderItemFilters {
@@ -1,7 +1,10 @@
+pub use crate::types::invoice::*;
+
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::json;
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::money::Money;
+use crate::utils::{Response, ResponseData, ResponseMeta, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderResponse {
@@ -23,18 +26,91 @@ pub struct OrderResponse {
     pub total_usd: Option<i64>,
     pub tax_name: Option<String>,
     pub tax_rate: Option<String>,
-    pub status: Option<String>,
+    pub status: Option<OrderStatus>,
     pub status_formatted: Option<String>,
     pub refunded: bool,
-    pub refunded_at: Option<Value>,
+    pub refunded_at: Option<Timestamp>,
+    #[serde(default)]
+    pub refunded_amount: Option<i64>,
+    #[serde(default)]
+    pub refunded_amount_usd: Option<i64>,
+    #[serde(default)]
+    pub refunded_amount_formatted: Option<String>,
     pub subtotal_formatted: Option<String>,
     pub discount_total_formatted: Option<String>,
     pub tax_formatted: Option<String>,
     pub total_formatted: Option<String>,
     pub first_order_item: Option<FirstOrderItem>,
     pub urls: Urls,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+string_enum! {
+    /// # Example
+    /// ```
+    /// use lemonsqueezy::orders::OrderStatus;
+    ///
+    /// let status: OrderStatus = serde_json::from_str("\"partial_refund\"").unwrap();
+    /// assert_eq!(status, OrderStatus::PartialRefund);
+    ///
+    /// let status: OrderStatus = serde_json::from_str("\"disputed\"").unwrap();
+    /// assert_eq!(status, OrderStatus::Unknown("disputed".to_string()));
+    /// assert_eq!(serde_json::to_string(&status).unwrap(), "\"disputed\"");
+    /// ```
+    pub enum OrderStatus {
+        Pending => "pending",
+        Failed => "failed",
+        Paid => "paid",
+        Refunded => "refunded",
+        PartialRefund => "partial_refund",
+        Fraudulent => "fraudulent",
+    }
+}
+
+impl OrderResponse {
+    pub fn subtotal_money(&self) -> Option<Money> {
+        self.subtotal
+            .map(|amount| Money::new(amount, &self.currency))
+    }
+
+    pub fn discount_total_money(&self) -> Option<Money> {
+        self.discount_total
+            .map(|amount| Money::new(amount, &self.currency))
+    }
+
+    pub fn tax_money(&self) -> Option<Money> {
+        self.tax.map(|amount| Money::new(amount, &self.currency))
+    }
+
+    pub fn total_money(&self) -> Option<Money> {
+        self.total.map(|amount| Money::new(amount, &self.currency))
+    }
+
+    pub fn refunded_amount_money(&self) -> Option<Money> {
+        self.refunded_amount
+            .map(|amount| Money::new(amount, &self.currency))
+    }
+
+    pub fn subtotal_usd_money(&self) -> Option<Money> {
+        self.subtotal_usd.map(Money::usd)
+    }
+
+    pub fn discount_total_usd_money(&self) -> Option<Money> {
+        self.discount_total_usd.map(Money::usd)
+    }
+
+    pub fn tax_usd_money(&self) -> Option<Money> {
+        self.tax_usd.map(Money::usd)
+    }
+
+    pub fn total_usd_money(&self) -> Option<Money> {
+        self.total_usd.map(Money::usd)
+    }
+
+    pub fn refunded_amount_usd_money(&self) -> Option<Money> {
+        self.refunded_amount_usd.map(Money::usd)
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -46,8 +122,8 @@ pub struct FirstOrderItem {
     pub product_name: String,
     pub variant_name: String,
     pub price: Option<i64>,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
     pub test_mode: bool,
 }
 
@@ -142,4 +218,84 @@ impl Order {
 
         Ok(response)
     }
+
+    /// Refund an order
+    ///
+    /// # Arguments
+    /// * `order_id` - The order id
+    /// * `amount` - The amount to refund in cents, `None` for a full refund
+    ///
+    /// # Returns
+    /// `Result<Response<OrderResponse>, crate::errors::NetworkError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::orders::Order;
+    /// let orders = Order::build(lemonsqueezy);
+    /// let order = orders.refund(123, Some(500)).await.unwrap();
+    /// ```
+    ///
+    /// Read More: https://docs.lemonsqueezy.com/api/orders#issue-a-refund
+    pub async fn refund(
+        &self,
+        order_id: usize,
+        amount: Option<i64>,
+    ) -> anyhow::Result<Response<OrderResponse>, crate::errors::NetworkError> {
+        let data = json!({
+            "data": {
+                "type": "orders",
+                "id": order_id.to_string(),
+                "attributes": RefundAttributes { amount },
+            }
+        });
+
+        let response = self
+            .api
+            .post(&format!("/v1/orders/{}/refund", order_id), data)
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Generate a downloadable invoice for an order
+    ///
+    /// # Arguments
+    /// * `order_id` - The order id
+    /// * `details` - The billing details to print on the invoice
+    ///
+    /// # Returns
+    /// `Result<ResponseMeta<GeneratedInvoice>, crate::errors::NetworkError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::orders::{InvoiceDetails, Order};
+    /// let orders = Order::build(lemonsqueezy);
+    /// let invoice = orders.generate_invoice(123, InvoiceDetails {
+    ///     name: "John Doe".to_string(),
+    ///     address: "123 Main St".to_string(),
+    ///     city: "New York".to_string(),
+    ///     zip_code: "10001".to_string(),
+    ///     country: "US".to_string(),
+    ///     ..Default::default()
+    /// }).await.unwrap();
+    /// ```
+    ///
+    /// Read More: https://docs.lemonsqueezy.com/api/orders#generate-order-invoice
+    pub async fn generate_invoice(
+        &self,
+        order_id: usize,
+        details: InvoiceDetails,
+    ) -> anyhow::Result<ResponseMeta<GeneratedInvoice>, crate::errors::NetworkError> {
+        let query = serde_urlencoded::to_string(&details).unwrap();
+
+        let response = self
+            .api
+            .post(
+                &format!("/v1/orders/{}/generate-invoice?{}", order_id, query),
+                json!({}),
+            )
+            .await?;
+
+        Ok(response)
+    }
 }
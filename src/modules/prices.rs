@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::money::Money;
+use crate::subscription_items::IntervalUnit;
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Tier {
@@ -19,15 +21,22 @@ pub struct PriceResponse {
     pub unit_price: i64,
     pub package_size: Option<i64>,
     pub tiers: Option<Vec<Tier>>,
-    pub renewal_interval_unit: Option<String>,
+    pub renewal_interval_unit: Option<IntervalUnit>,
     pub renewal_interval_quantity: Option<i64>,
-    pub trial_interval_unit: Option<String>,
+    pub trial_interval_unit: Option<IntervalUnit>,
     pub trial_interval_quantity: Option<i64>,
     pub min_price: Option<Value>,
     pub suggested_price: Option<Value>,
     pub tax_code: Option<String>,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+impl PriceResponse {
+    /// The unit price, prices are in the currency of the store
+    pub fn unit_price_money(&self, store_currency: &str) -> Money {
+        Money::new(self.unit_price, store_currency)
+    }
 }
 
 pub struct PriceFilters {
@@ -0,0 +1,400 @@
+pub use crate::errors::PricingError;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::prices::{PriceResponse, Tier};
+
+/// An itemized price, amounts are in cents
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quote {
+    pub lines: Vec<QuoteLine>,
+    pub total: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuoteLine {
+    /// Index of the tier in `PriceResponse.tiers`, `None` for the standard and package schemes
+    pub tier: Option<usize>,
+    /// Number of units, or of packages for the package scheme
+    pub quantity: i64,
+    pub unit_price: i64,
+    pub fixed_fee: i64,
+    /// `quantity * unit_price + fixed_fee`
+    pub amount: i64,
+}
+
+impl QuoteLine {
+    fn new(
+        tier: Option<usize>,
+        quantity: i64,
+        unit_price: i64,
+        fixed_fee: i64,
+    ) -> Result<Self, PricingError> {
+        let amount = quantity
+            .checked_mul(unit_price)
+            .and_then(|amount| amount.checked_add(fixed_fee))
+            .ok_or(PricingError::Overflow)?;
+
+        Ok(Self {
+            tier,
+            quantity,
+            unit_price,
+            fixed_fee,
+            amount,
+        })
+    }
+}
+
+/// Compute the price of `quantity` units
+///
+/// - `standard`: `quantity * unit_price`
+/// - `package`: the quantity is rounded up to whole packages of `package_size` units,
+///   each package costs `unit_price`
+/// - `graduated`: each tier prices the units that fall into it, plus its `fixed_fee`
+/// - `volume`: the tier the total quantity falls into prices every unit, plus its `fixed_fee`
+///
+/// A tier covers the units up to its `last_unit` (inclusive), a `last_unit` of `"inf"` or `null`
+/// is unbounded.
+///
+/// # Arguments
+/// - price: The price to quote
+/// - quantity: The number of units
+///
+/// # Returns
+/// - `Result<Quote, PricingError>` object
+///
+/// # Example
+/// ```
+/// use lemonsqueezy::prices::{PriceResponse, Tier};
+/// use lemonsqueezy::pricing;
+///
+/// let price = PriceResponse {
+///     scheme: "graduated".to_string(),
+///     tiers: Some(vec![
+///         Tier { last_unit: Some(10.into()), unit_price: Some(100), fixed_fee: Some(500) },
+///         Tier { last_unit: Some("inf".into()), unit_price: Some(50), fixed_fee: None },
+///     ]),
+///     ..Default::default()
+/// };
+///
+/// let quote = pricing::quote(&price, 15).unwrap();
+/// assert_eq!(quote.lines.len(), 2);
+/// assert_eq!(quote.lines[0].amount, 10 * 100 + 500);
+/// assert_eq!(quote.lines[1].amount, 5 * 50);
+/// assert_eq!(quote.total, 1750);
+///
+/// let price = PriceResponse { scheme: "volume".to_string(), ..price };
+/// assert_eq!(pricing::quote(&price, 15).unwrap().total, 15 * 50);
+///
+/// let price = PriceResponse {
+///     scheme: "package".to_string(),
+///     unit_price: 1000,
+///     package_size: Some(10),
+///     ..Default::default()
+/// };
+/// assert_eq!(pricing::quote(&price, 15).unwrap().total, 2000);
+/// ```
+pub fn quote(price: &PriceResponse, quantity: i64) -> Result<Quote, PricingError> {
+    if quantity < 0 {
+        return Err(PricingError::NegativeQuantity(quantity));
+    }
+
+    let lines = match price.scheme.as_str() {
+        "standard" => vec![QuoteLine::new(None, quantity, price.unit_price, 0)?],
+        "package" => {
+            let package_size = price
+                .package_size
+                .filter(|size| *size > 0)
+                .ok_or(PricingError::InvalidPackageSize(price.package_size))?;
+
+            let packages = quantity
+                .checked_add(package_size - 1)
+                .ok_or(PricingError::Overflow)?
+                / package_size;
+
+            vec![QuoteLine::new(None, packages, price.unit_price, 0)?]
+        }
+        "graduated" => graduated(tiers(price)?, quantity)?,
+        "volume" => volume(tiers(price)?, quantity)?,
+        scheme => return Err(PricingError::UnknownScheme(scheme.to_string())),
+    };
+
+    let total = lines
+        .iter()
+        .try_fold(0i64, |total, line| total.checked_add(line.amount))
+        .ok_or(PricingError::Overflow)?;
+
+    Ok(Quote { lines, total })
+}
+
+fn tiers(price: &PriceResponse) -> Result<&[Tier], PricingError> {
+    price
+        .tiers
+        .as_deref()
+        .filter(|tiers| !tiers.is_empty())
+        .ok_or_else(|| PricingError::MissingTiers(price.scheme.clone()))
+}
+
+fn graduated(tiers: &[Tier], quantity: i64) -> Result<Vec<QuoteLine>, PricingError> {
+    let mut lines = Vec::new();
+    let mut priced = 0;
+
+    for (index, tier) in tiers.iter().enumerate() {
+        if priced >= quantity {
+            break;
+        }
+
+        let upper = last_unit(tier)?.map_or(quantity, |last_unit| last_unit.min(quantity));
+
+        if upper > priced {
+            lines.push(QuoteLine::new(
+                Some(index),
+                upper - priced,
+                tier.unit_price.unwrap_or(0),
+                tier.fixed_fee.unwrap_or(0),
+            )?);
+
+            priced = upper;
+        }
+    }
+
+    if priced < quantity {
+        return Err(PricingError::QuantityAboveTiers(quantity));
+    }
+
+    Ok(lines)
+}
+
+fn volume(tiers: &[Tier], quantity: i64) -> Result<Vec<QuoteLine>, PricingError> {
+    if quantity == 0 {
+        return Ok(Vec::new());
+    }
+
+    for (index, tier) in tiers.iter().enumerate() {
+        if last_unit(tier)?.is_none_or(|last_unit| quantity <= last_unit) {
+            return Ok(vec![QuoteLine::new(
+                Some(index),
+                quantity,
+                tier.unit_price.unwrap_or(0),
+                tier.fixed_fee.unwrap_or(0),
+            )?]);
+        }
+    }
+
+    Err(PricingError::QuantityAboveTiers(quantity))
+}
+
+/// `None` if the tier is unbounded
+fn last_unit(tier: &Tier) -> Result<Option<i64>, PricingError> {
+    match &tier.last_unit {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(last_unit)) if last_unit == "inf" => Ok(None),
+        Some(Value::String(last_unit)) => last_unit
+            .parse()
+            .map(Some)
+            .map_err(|_| PricingError::InvalidTier(last_unit.clone())),
+        Some(Value::Number(last_unit)) => last_unit
+            .as_i64()
+            .map(Some)
+            .ok_or_else(|| PricingError::InvalidTier(last_unit.to_string())),
+        Some(last_unit) => Err(PricingError::InvalidTier(last_unit.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier(last_unit: Option<i64>, unit_price: i64, fixed_fee: i64) -> Tier {
+        Tier {
+            last_unit: Some(last_unit.map_or_else(|| "inf".into(), Into::into)),
+            unit_price: Some(unit_price),
+            fixed_fee: Some(fixed_fee),
+        }
+    }
+
+    /// Tier layouts with increasing unit prices and no fixed fee
+    fn increasing_tier_sets() -> Vec<Vec<Tier>> {
+        vec![
+            vec![tier(None, 100, 0)],
+            vec![tier(Some(10), 50, 0), tier(None, 100, 0)],
+            vec![
+                tier(Some(1), 0, 0),
+                tier(Some(5), 1, 0),
+                tier(None, 1000, 0),
+            ],
+            vec![
+                tier(Some(3), 250, 0),
+                tier(Some(7), 250, 0),
+                tier(None, 300, 0),
+            ],
+            vec![
+                tier(Some(3), 0, 0),
+                tier(Some(7), 250, 0),
+                tier(None, 250, 0),
+            ],
+        ]
+    }
+
+    /// Tier layouts with decreasing unit prices (volume discounts), some with fixed fees
+    fn decreasing_tier_sets() -> Vec<Vec<Tier>> {
+        vec![
+            vec![tier(None, 100, 0)],
+            vec![tier(Some(10), 100, 0), tier(None, 50, 0)],
+            vec![
+                tier(Some(1), 1000, 0),
+                tier(Some(5), 500, 0),
+                tier(None, 1, 0),
+            ],
+            vec![
+                tier(Some(10), 100, 500),
+                tier(Some(100), 80, 200),
+                tier(None, 60, 100),
+            ],
+        ]
+    }
+
+    fn price(scheme: &str, tiers: &[Tier]) -> PriceResponse {
+        PriceResponse {
+            scheme: scheme.to_string(),
+            tiers: Some(tiers.to_vec()),
+            ..Default::default()
+        }
+    }
+
+    fn total(price: &PriceResponse, quantity: i64) -> i64 {
+        quote(price, quantity).unwrap().total
+    }
+
+    #[test]
+    fn total_never_decreases_as_quantity_grows() {
+        let mut prices = vec![
+            PriceResponse {
+                scheme: "standard".to_string(),
+                unit_price: 999,
+                ..Default::default()
+            },
+            PriceResponse {
+                scheme: "package".to_string(),
+                unit_price: 1000,
+                package_size: Some(7),
+                ..Default::default()
+            },
+        ];
+        prices.extend(
+            increasing_tier_sets()
+                .iter()
+                .chain(&decreasing_tier_sets())
+                .map(|tiers| price("graduated", tiers)),
+        );
+        // the volume total drops at the tier boundaries when the unit price decreases
+        prices.extend(
+            increasing_tier_sets()
+                .iter()
+                .map(|tiers| price("volume", tiers)),
+        );
+
+        for price in &prices {
+            for quantity in 0..500 {
+                assert!(
+                    total(price, quantity + 1) >= total(price, quantity),
+                    "{} total decreases at {}",
+                    price.scheme,
+                    quantity
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn graduated_is_never_above_volume_with_increasing_unit_prices() {
+        for tiers in increasing_tier_sets() {
+            let graduated = price("graduated", &tiers);
+            let volume = price("volume", &tiers);
+
+            for quantity in 0..500 {
+                assert!(
+                    total(&graduated, quantity) <= total(&volume, quantity),
+                    "graduated above volume at {} for {:?}",
+                    quantity,
+                    tiers
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn graduated_is_never_below_volume_with_decreasing_unit_prices() {
+        for tiers in decreasing_tier_sets() {
+            let graduated = price("graduated", &tiers);
+            let volume = price("volume", &tiers);
+
+            for quantity in 0..500 {
+                assert!(
+                    total(&graduated, quantity) >= total(&volume, quantity),
+                    "graduated below volume at {} for {:?}",
+                    quantity,
+                    tiers
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn package_is_whole_packages_times_price() {
+        for package_size in 1..=25 {
+            let price = PriceResponse {
+                scheme: "package".to_string(),
+                unit_price: 1000,
+                package_size: Some(package_size),
+                ..Default::default()
+            };
+
+            for quantity in 0..300i64 {
+                let packages = (quantity as f64 / package_size as f64).ceil() as i64;
+
+                assert_eq!(total(&price, quantity), packages * 1000);
+            }
+        }
+    }
+
+    #[test]
+    fn standard_is_quantity_times_unit_price() {
+        let price = PriceResponse {
+            scheme: "standard".to_string(),
+            unit_price: 1999,
+            ..Default::default()
+        };
+
+        for quantity in 0..300 {
+            assert_eq!(total(&price, quantity), quantity * 1999);
+        }
+    }
+
+    #[test]
+    fn package_rounding_overflow_is_an_error() {
+        let price = PriceResponse {
+            scheme: "package".to_string(),
+            unit_price: 1,
+            package_size: Some(10),
+            ..Default::default()
+        };
+
+        assert_eq!(quote(&price, i64::MAX), Err(PricingError::Overflow));
+        assert_eq!(
+            quote(&price, i64::MAX - 9).unwrap().lines[0].quantity,
+            i64::MAX / 10
+        );
+    }
+
+    #[test]
+    fn negative_quantities_are_rejected() {
+        for tiers in decreasing_tier_sets() {
+            assert_eq!(
+                quote(&price("graduated", &tiers), -1),
+                Err(PricingError::NegativeQuantity(-1))
+            );
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProductResponse {
@@ -9,7 +9,7 @@ pub struct ProductResponse {
     pub name: String,
     pub slug: Option<String>,
     pub description: Option<String>,
-    pub status: Option<String>,
+    pub status: Option<ProductStatus>,
     pub status_formatted: Option<String>,
     pub thumb_url: Option<String>,
     pub large_thumb_url: Option<String>,
@@ -19,8 +19,15 @@ pub struct ProductResponse {
     pub to_price: Option<Value>,
     pub buy_now_url: Option<String>,
     pub price_formatted: Option<String>,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+string_enum! {
+    pub enum ProductStatus {
+        Draft => "draft",
+        Published => "published",
+    }
 }
 
 pub struct ProductFilters {
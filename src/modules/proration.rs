@@ -0,0 +1,485 @@
+//! Local estimate of what a plan change costs, to show before calling `Subscriptions::change_variant`.
+//!
+//! This is an estimate: taxes, discounts and the exact rounding applied by LemonSqueezy are not
+//! taken into account, the invoice issued by LemonSqueezy is authoritative. Usage-based prices
+//! can't be estimated and are rejected.
+
+pub use crate::errors::ProrationError;
+
+use chrono::{DateTime, Datelike, Duration, Months, Utc};
+
+use crate::prices::PriceResponse;
+use crate::pricing;
+use crate::subscription_items::IntervalUnit;
+use crate::subscriptions::SubscriptionResponse;
+use crate::utils::ToDateTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanChange {
+    /// Same billing interval, more expensive plan
+    Upgrade,
+    /// Same billing interval, cheaper plan
+    Downgrade,
+    /// Different billing interval, a new billing period starts right away
+    IntervalChange,
+    /// Same billing interval and price
+    NoChange,
+}
+
+/// Amounts are in cents, in the currency of the store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProrationPreview {
+    pub change: PlanChange,
+    /// Unused value of the current plan for the rest of the billing period
+    pub credit: i64,
+    /// Cost of the new plan, for the rest of the billing period (or a whole new one on interval changes)
+    pub charge: i64,
+    /// What the customer pays today, `charge - credit` if positive
+    pub amount_due: i64,
+    /// Credit left for future invoices, `credit - charge` if positive
+    pub remaining_credit: i64,
+}
+
+/// Estimate the proration of moving a subscription from the `current` price to the `new` one
+///
+/// The prices are quoted with `pricing::quote` for the quantity of the subscription, so every
+/// pricing scheme is supported. The billing period ends at `renews_at` and, for monthly and yearly
+/// intervals, starts on the `billing_anchor` day of the month.
+///
+/// # Arguments
+/// - subscription: The subscription to change the plan of
+/// - current: The price of the current variant
+/// - new: The price of the variant to move to
+/// - now: The time at which the change happens
+///
+/// # Returns
+/// - `Result<ProrationPreview, ProrationError>` object
+///
+/// # Example
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use lemonsqueezy::prices::PriceResponse;
+/// use lemonsqueezy::proration::{self, PlanChange};
+/// use lemonsqueezy::subscription_items::IntervalUnit;
+/// use lemonsqueezy::subscriptions::SubscriptionResponse;
+///
+/// let monthly = |unit_price| PriceResponse {
+///     scheme: "standard".to_string(),
+///     unit_price,
+///     renewal_interval_unit: Some(IntervalUnit::Month),
+///     renewal_interval_quantity: Some(1),
+///     ..Default::default()
+/// };
+///
+/// let subscription = SubscriptionResponse {
+///     renews_at: "2023-07-01T00:00:00.000000Z".parse().unwrap(),
+///     billing_anchor: 1,
+///     ..Default::default()
+/// };
+///
+/// // half-way through June
+/// let now = Utc.with_ymd_and_hms(2023, 6, 16, 0, 0, 0).unwrap();
+/// let preview = proration::preview(&subscription, &monthly(1000), &monthly(2000), now).unwrap();
+///
+/// assert_eq!(preview.change, PlanChange::Upgrade);
+/// assert_eq!(preview.credit, 500);
+/// assert_eq!(preview.charge, 1000);
+/// assert_eq!(preview.amount_due, 500);
+/// ```
+pub fn preview(
+    subscription: &SubscriptionResponse,
+    current: &PriceResponse,
+    new: &PriceResponse,
+    now: DateTime<Utc>,
+) -> Result<ProrationPreview, ProrationError> {
+    let period_end = subscription
+        .renews_at
+        .to_datetime()
+        .ok_or_else(|| ProrationError::InvalidRenewalDate(subscription.renews_at.to_string()))?;
+    let period_start = interval_start(current, period_end, subscription.billing_anchor)?;
+
+    let period = (period_end - period_start).num_seconds().max(0);
+    let remaining = (period_end - now).num_seconds().clamp(0, period);
+
+    let quantity = subscription.first_subscription_item.quantity.max(1);
+    let current_total = total(current, quantity)?;
+    let new_total = total(new, quantity)?;
+
+    let interval_changed = current.renewal_interval_unit != new.renewal_interval_unit
+        || current.renewal_interval_quantity != new.renewal_interval_quantity;
+
+    let change = if interval_changed {
+        PlanChange::IntervalChange
+    } else if new_total > current_total {
+        PlanChange::Upgrade
+    } else if new_total < current_total {
+        PlanChange::Downgrade
+    } else {
+        PlanChange::NoChange
+    };
+
+    let credit = prorate(current_total, remaining, period);
+    let charge = match change {
+        PlanChange::IntervalChange => {
+            // validates the new interval
+            interval_start(new, now, subscription.billing_anchor)?;
+            new_total
+        }
+        _ => prorate(new_total, remaining, period),
+    };
+
+    Ok(ProrationPreview {
+        change,
+        credit,
+        charge,
+        amount_due: (charge - credit).max(0),
+        remaining_credit: (credit - charge).max(0),
+    })
+}
+
+/// `amount * remaining / period`, rounded half away from zero
+///
+/// `remaining` is at most `period`, so the result is at most `amount` in absolute value.
+fn prorate(amount: i64, remaining: i64, period: i64) -> i64 {
+    if period == 0 {
+        return 0;
+    }
+
+    let numerator = i128::from(amount) * i128::from(remaining);
+    let period = i128::from(period);
+    let half = if numerator < 0 {
+        -period / 2
+    } else {
+        period / 2
+    };
+
+    ((numerator + half) / period) as i64
+}
+
+/// The price of `quantity` units for a whole billing period
+fn total(price: &PriceResponse, quantity: i64) -> Result<i64, ProrationError> {
+    if !price.usage_aggregation.is_null() {
+        return Err(ProrationError::UsageBased(price.variant_id));
+    }
+
+    Ok(pricing::quote(price, quantity)?.total)
+}
+
+/// The start of the billing period ending at `period_end`
+///
+/// Monthly and yearly periods start on the `billing_anchor` day of the month, or on the last day
+/// of a shorter month, e.g. a period anchored on the 31st ending on March 31st starts on February
+/// 28th or 29th.
+fn interval_start(
+    price: &PriceResponse,
+    period_end: DateTime<Utc>,
+    billing_anchor: i64,
+) -> Result<DateTime<Utc>, ProrationError> {
+    let unit = price
+        .renewal_interval_unit
+        .as_ref()
+        .ok_or(ProrationError::NotRecurring(price.variant_id))?;
+    let quantity = price.renewal_interval_quantity.unwrap_or(1).max(1);
+
+    let out_of_range = || ProrationError::IntervalOutOfRange(quantity, unit.to_string());
+
+    let months = |per_unit: u32| {
+        u32::try_from(quantity)
+            .ok()
+            .and_then(|quantity| quantity.checked_mul(per_unit))
+            .map(Months::new)
+            .ok_or_else(out_of_range)
+    };
+
+    let start = match unit {
+        IntervalUnit::Day => Duration::try_days(quantity)
+            .and_then(|interval| period_end.checked_sub_signed(interval))
+            .ok_or_else(out_of_range)?,
+        IntervalUnit::Week => Duration::try_weeks(quantity)
+            .and_then(|interval| period_end.checked_sub_signed(interval))
+            .ok_or_else(out_of_range)?,
+        IntervalUnit::Month => period_end
+            .checked_sub_months(months(1)?)
+            .map(|start| anchored(start, billing_anchor))
+            .ok_or_else(out_of_range)?,
+        IntervalUnit::Year => period_end
+            .checked_sub_months(months(12)?)
+            .map(|start| anchored(start, billing_anchor))
+            .ok_or_else(out_of_range)?,
+        IntervalUnit::Unknown(unit) => {
+            return Err(ProrationError::UnsupportedInterval(unit.clone()))
+        }
+    };
+
+    Ok(start)
+}
+
+/// Move `date` to the `billing_anchor` day of its month, capped at the last day of the month
+fn anchored(date: DateTime<Utc>, billing_anchor: i64) -> DateTime<Utc> {
+    let Ok(anchor @ 1..=31) = u32::try_from(billing_anchor) else {
+        return date;
+    };
+
+    (1..=anchor)
+        .rev()
+        .find_map(|day| date.with_day(day))
+        .unwrap_or(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prices::Tier;
+    use crate::subscriptions::FirstSubscriptionItem;
+
+    fn monthly(scheme: &str) -> PriceResponse {
+        PriceResponse {
+            scheme: scheme.to_string(),
+            renewal_interval_unit: Some(IntervalUnit::Month),
+            renewal_interval_quantity: Some(1),
+            ..Default::default()
+        }
+    }
+
+    fn subscription(quantity: i64) -> SubscriptionResponse {
+        SubscriptionResponse {
+            renews_at: "2023-07-01T00:00:00.000000Z".parse().unwrap(),
+            billing_anchor: 1,
+            first_subscription_item: FirstSubscriptionItem {
+                quantity,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn mid_june() -> DateTime<Utc> {
+        "2023-06-16T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn prorates_package_prices_by_package() {
+        let current = PriceResponse {
+            unit_price: 1000,
+            package_size: Some(10),
+            ..monthly("package")
+        };
+        let new = PriceResponse {
+            unit_price: 3000,
+            ..current.clone()
+        };
+
+        // 15 units are 2 packages
+        let preview = preview(&subscription(15), &current, &new, mid_june()).unwrap();
+
+        assert_eq!(preview.credit, 1000);
+        assert_eq!(preview.charge, 3000);
+        assert_eq!(preview.amount_due, 2000);
+    }
+
+    #[test]
+    fn prorates_graduated_prices_by_tier() {
+        let tiers = |unit_price| {
+            Some(vec![
+                Tier {
+                    last_unit: Some(5.into()),
+                    unit_price: Some(unit_price),
+                    fixed_fee: None,
+                },
+                Tier {
+                    last_unit: Some("inf".into()),
+                    unit_price: Some(unit_price / 2),
+                    fixed_fee: None,
+                },
+            ])
+        };
+        let current = PriceResponse {
+            tiers: tiers(200),
+            ..monthly("graduated")
+        };
+        let new = PriceResponse {
+            tiers: tiers(400),
+            ..monthly("graduated")
+        };
+
+        // 5 * 200 + 5 * 100 and 5 * 400 + 5 * 200
+        let preview = preview(&subscription(10), &current, &new, mid_june()).unwrap();
+
+        assert_eq!(preview.change, PlanChange::Upgrade);
+        assert_eq!(preview.credit, 750);
+        assert_eq!(preview.charge, 1500);
+    }
+
+    fn standard(unit_price: i64) -> PriceResponse {
+        PriceResponse {
+            unit_price,
+            ..monthly("standard")
+        }
+    }
+
+    #[test]
+    fn credits_the_difference_on_downgrades() {
+        let preview = preview(
+            &subscription(1),
+            &standard(2000),
+            &standard(1000),
+            mid_june(),
+        )
+        .unwrap();
+
+        assert_eq!(preview.change, PlanChange::Downgrade);
+        assert_eq!(preview.credit, 1000);
+        assert_eq!(preview.charge, 500);
+        assert_eq!(preview.amount_due, 0);
+        assert_eq!(preview.remaining_credit, 500);
+    }
+
+    #[test]
+    fn charges_a_whole_period_on_interval_changes() {
+        let yearly = PriceResponse {
+            renewal_interval_unit: Some(IntervalUnit::Year),
+            ..standard(10000)
+        };
+
+        let preview = preview(&subscription(1), &standard(1000), &yearly, mid_june()).unwrap();
+
+        assert_eq!(preview.change, PlanChange::IntervalChange);
+        assert_eq!(preview.credit, 500);
+        assert_eq!(preview.charge, 10000);
+        assert_eq!(preview.amount_due, 9500);
+        assert_eq!(preview.remaining_credit, 0);
+    }
+
+    #[test]
+    fn nothing_is_due_without_a_change() {
+        let preview = preview(
+            &subscription(1),
+            &standard(1000),
+            &standard(1000),
+            mid_june(),
+        )
+        .unwrap();
+
+        assert_eq!(preview.change, PlanChange::NoChange);
+        assert_eq!(preview.credit, preview.charge);
+        assert_eq!(preview.amount_due, 0);
+        assert_eq!(preview.remaining_credit, 0);
+    }
+
+    #[test]
+    fn prorates_with_exact_rounding() {
+        // a third of the period is left
+        assert_eq!(prorate(1000, 10, 30), 333);
+        assert_eq!(prorate(2000, 10, 30), 667);
+        assert_eq!(prorate(1, 1, 2), 1);
+        assert_eq!(prorate(-1, 1, 2), -1);
+        assert_eq!(
+            prorate(i64::MAX, 86_400 * 30, 86_400 * 31),
+            8925843906633654007
+        );
+        assert_eq!(prorate(1000, 0, 30), 0);
+        assert_eq!(prorate(1000, 30, 30), 1000);
+        assert_eq!(prorate(1000, 0, 0), 0);
+    }
+
+    #[test]
+    fn the_whole_plan_is_prorated_before_the_period_and_nothing_after() {
+        let before = "2023-05-01T00:00:00Z".parse().unwrap();
+        let after = "2023-07-02T00:00:00Z".parse().unwrap();
+
+        let preview_before =
+            preview(&subscription(1), &standard(1000), &standard(2000), before).unwrap();
+        let preview_after =
+            preview(&subscription(1), &standard(1000), &standard(2000), after).unwrap();
+
+        assert_eq!((preview_before.credit, preview_before.charge), (1000, 2000));
+        assert_eq!((preview_after.credit, preview_after.charge), (0, 0));
+    }
+
+    #[test]
+    fn rejects_usage_based_prices() {
+        let current = PriceResponse {
+            unit_price: 10,
+            usage_aggregation: "sum".into(),
+            ..monthly("standard")
+        };
+
+        let result = preview(&subscription(1), &current, &monthly("standard"), mid_june());
+
+        assert!(matches!(result, Err(ProrationError::UsageBased(_))));
+    }
+
+    #[test]
+    fn starts_monthly_periods_on_the_billing_anchor() {
+        let price = monthly("standard");
+        let start = |period_end: &str, billing_anchor| {
+            interval_start(&price, period_end.parse().unwrap(), billing_anchor)
+                .unwrap()
+                .to_rfc3339()
+        };
+
+        assert_eq!(
+            start("2024-03-31T00:00:00Z", 31),
+            "2024-02-29T00:00:00+00:00"
+        );
+        assert_eq!(
+            start("2024-03-30T00:00:00Z", 30),
+            "2024-02-29T00:00:00+00:00"
+        );
+        assert_eq!(
+            start("2023-03-31T00:00:00Z", 31),
+            "2023-02-28T00:00:00+00:00"
+        );
+        assert_eq!(
+            start("2024-04-30T00:00:00Z", 31),
+            "2024-03-31T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_intervals() {
+        let period_end = "2023-07-01T00:00:00Z".parse().unwrap();
+
+        for (unit, quantity) in [
+            (IntervalUnit::Day, i64::MAX),
+            (IntervalUnit::Week, i64::MAX),
+            (IntervalUnit::Month, i64::from(u32::MAX) + 1),
+            (IntervalUnit::Year, i64::from(u32::MAX / 12 + 1)),
+        ] {
+            let price = PriceResponse {
+                renewal_interval_unit: Some(unit),
+                renewal_interval_quantity: Some(quantity),
+                ..Default::default()
+            };
+
+            assert!(matches!(
+                interval_start(&price, period_end, 1),
+                Err(ProrationError::IntervalOutOfRange(..))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_interval_units() {
+        let price: PriceResponse = serde_json::from_value(serde_json::json!({
+            "variant_id": 1,
+            "category": "subscription",
+            "scheme": "standard",
+            "usage_aggregation": null,
+            "unit_price": 1000,
+            "renewal_interval_unit": "fortnight",
+            "created_at": "2023-06-01T00:00:00.000000Z",
+            "updated_at": "2023-06-01T00:00:00.000000Z"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            price.renewal_interval_unit,
+            Some(IntervalUnit::Unknown("fortnight".to_string()))
+        );
+        assert!(matches!(
+            interval_start(&price, "2023-07-01T00:00:00Z".parse().unwrap(), 1),
+            Err(ProrationError::UnsupportedInterval(unit)) if unit == "fortnight"
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::money::Money;
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StoreResponse {
@@ -24,9 +25,19 @@ pub struct StoreResponse {
     #[serde(rename = "thirty_day_revenue")]
     pub thirty_day_revenue: i64,
     #[serde(rename = "created_at")]
-    pub created_at: String,
+    pub created_at: Timestamp,
     #[serde(rename = "updated_at")]
-    pub updated_at: String,
+    pub updated_at: Timestamp,
+}
+
+impl StoreResponse {
+    pub fn total_revenue_money(&self) -> Money {
+        Money::new(self.total_revenue, &self.currency)
+    }
+
+    pub fn thirty_day_revenue_money(&self) -> Money {
+        Money::new(self.thirty_day_revenue, &self.currency)
+    }
 }
 
 pub struct Store {
@@ -1,7 +1,10 @@
+pub use crate::types::invoice::*;
+
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::json;
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::money::Money;
+use crate::utils::{Response, ResponseData, ResponseMeta, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubscriptionInvoiceResponse {
@@ -23,28 +26,82 @@ pub struct SubscriptionInvoiceResponse {
     pub discount_total_usd: i64,
     pub tax_usd: i64,
     pub total_usd: i64,
-    pub status: String,
+    pub status: SubscriptionInvoiceStatus,
     pub status_formatted: String,
     pub refunded: bool,
-    pub refunded_at: Option<Value>,
+    pub refunded_at: Option<Timestamp>,
+    #[serde(default)]
+    pub refunded_amount: Option<i64>,
+    #[serde(default)]
+    pub refunded_amount_formatted: Option<String>,
     pub subtotal_formatted: String,
     pub discount_total_formatted: String,
     pub tax_formatted: String,
     pub total_formatted: String,
     pub urls: Urls,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
     pub test_mode: bool,
 }
 
+string_enum! {
+    pub enum SubscriptionInvoiceStatus {
+        Pending => "pending",
+        Paid => "paid",
+        Void => "void",
+        Refunded => "refunded",
+        PartialRefund => "partial_refund",
+    }
+}
+
+impl SubscriptionInvoiceResponse {
+    pub fn subtotal_money(&self) -> Money {
+        Money::new(self.subtotal, &self.currency)
+    }
+
+    pub fn discount_total_money(&self) -> Money {
+        Money::new(self.discount_total, &self.currency)
+    }
+
+    pub fn tax_money(&self) -> Money {
+        Money::new(self.tax, &self.currency)
+    }
+
+    pub fn total_money(&self) -> Money {
+        Money::new(self.total, &self.currency)
+    }
+
+    pub fn refunded_amount_money(&self) -> Option<Money> {
+        self.refunded_amount
+            .map(|amount| Money::new(amount, &self.currency))
+    }
+
+    pub fn subtotal_usd_money(&self) -> Money {
+        Money::usd(self.subtotal_usd)
+    }
+
+    pub fn discount_total_usd_money(&self) -> Money {
+        Money::usd(self.discount_total_usd)
+    }
+
+    pub fn tax_usd_money(&self) -> Money {
+        Money::usd(self.tax_usd)
+    }
+
+    pub fn total_usd_money(&self) -> Money {
+        Money::usd(self.total_usd)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Urls {
-    pub invoice_url: String,
+    /// `None` until an invoice has been generated
+    pub invoice_url: Option<String>,
 }
 
 pub struct SubscriptionInvoiceFilter {
     pub store_id: Option<i64>,
-    pub status: Option<String>,
+    pub status: Option<SubscriptionInvoiceStatus>,
 
     pub refunded: Option<bool>,
     pub subscription_id: Option<i64>,
@@ -166,4 +223,93 @@ impl SubscriptionInvoice {
 
         Ok(response)
     }
+
+    /// Generate a downloadable invoice for a Subscription Invoice
+    ///
+    /// # Arguments
+    /// * `subscription_invoice_id` - The subscription invoice ID
+    /// * `details` - The billing details to print on the invoice
+    ///
+    /// # Returns
+    /// `anyhow::Result<ResponseMeta<GeneratedInvoice>, crate::errors::NetworkError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscription_invoice::{InvoiceDetails, SubscriptionInvoice};
+    /// let subscription_invoice = SubscriptionInvoice::build(lemonsqueezy);
+    /// let invoice = subscription_invoice.generate_invoice(1, InvoiceDetails {
+    ///     name: "John Doe".to_string(),
+    ///     address: "123 Main St".to_string(),
+    ///     city: "New York".to_string(),
+    ///     zip_code: "10001".to_string(),
+    ///     country: "US".to_string(),
+    ///     ..Default::default()
+    /// }).await;
+    /// ```
+    ///
+    /// Read More: https://docs.lemonsqueezy.com/api/subscription-invoices#generate-subscription-invoice
+    pub async fn generate_invoice(
+        &self,
+        subscription_invoice_id: usize,
+        details: InvoiceDetails,
+    ) -> anyhow::Result<ResponseMeta<GeneratedInvoice>, crate::errors::NetworkError> {
+        let query = serde_urlencoded::to_string(&details).unwrap();
+
+        let response = self
+            .api
+            .post(
+                &format!(
+                    "/v1/subscription-invoices/{}/generate-invoice?{}",
+                    subscription_invoice_id, query
+                ),
+                json!({}),
+            )
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Refund a Subscription Invoice
+    ///
+    /// # Arguments
+    /// * `subscription_invoice_id` - The subscription invoice ID
+    /// * `amount` - The amount to refund in cents, `None` for a full refund
+    ///
+    /// # Returns
+    /// `anyhow::Result<Response<SubscriptionInvoiceResponse>, crate::errors::NetworkError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscription_invoice::SubscriptionInvoice;
+    /// let subscription_invoice = SubscriptionInvoice::build(lemonsqueezy);
+    /// let subscription_invoice = subscription_invoice.refund(1, Some(500)).await;
+    /// ```
+    ///
+    /// Read More: https://docs.lemonsqueezy.com/api/subscription-invoices#issue-a-refund
+    pub async fn refund(
+        &self,
+        subscription_invoice_id: usize,
+        amount: Option<i64>,
+    ) -> anyhow::Result<Response<SubscriptionInvoiceResponse>, crate::errors::NetworkError> {
+        let data = json!({
+            "data": {
+                "type": "subscription-invoices",
+                "id": subscription_invoice_id.to_string(),
+                "attributes": RefundAttributes { amount },
+            }
+        });
+
+        let response = self
+            .api
+            .post(
+                &format!(
+                    "/v1/subscription-invoices/{}/refund",
+                    subscription_invoice_id
+                ),
+                data,
+            )
+            .await?;
+
+        Ok(response)
+    }
 }
@@ -1,7 +1,12 @@
+pub use crate::errors::UsageProjectionError;
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
-use crate::utils::{Response, ResponseData, ResponseMeta, VecResponse};
+use crate::prices::{PriceResponse, Prices};
+use crate::pricing::{self, Quote};
+use crate::utils::{Response, ResponseData, ResponseMeta, Timestamp, ToDateTime, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubscriptionItemResponse {
@@ -10,23 +15,89 @@ pub struct SubscriptionItemResponse {
     pub price_id: i64,
     pub quantity: i64,
     pub is_usage_based: bool,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubscriptionItemMetaResponse {
-    pub period_start: String,
-    pub period_end: String,
+    pub period_start: Timestamp,
+    pub period_end: Timestamp,
+    /// Usage of the current billing period
     pub quantity: i64,
-    pub interval_unit: String,
+    pub interval_unit: IntervalUnit,
     pub interval_quantity: i64,
 }
 
+impl SubscriptionItemMetaResponse {
+    /// Extrapolate the usage to the end of the billing period, at the rate recorded so far
+    ///
+    /// The usage is returned as-is once the period is over, or if the period can't be read.
+    ///
+    /// # Example
+    /// ```
+    /// use lemonsqueezy::subscription_items::SubscriptionItemMetaResponse;
+    ///
+    /// let usage = SubscriptionItemMetaResponse {
+    ///     period_start: "2023-06-01T00:00:00.000000Z".parse().unwrap(),
+    ///     period_end: "2023-07-01T00:00:00.000000Z".parse().unwrap(),
+    ///     quantity: 100,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// // a third of June has elapsed
+    /// let now = "2023-06-11T00:00:00Z".parse().unwrap();
+    /// assert_eq!(usage.projected_quantity(now), 300);
+    /// ```
+    pub fn projected_quantity(&self, now: DateTime<Utc>) -> i64 {
+        let (Some(period_start), Some(period_end)) = (
+            self.period_start.to_datetime(),
+            self.period_end.to_datetime(),
+        ) else {
+            return self.quantity;
+        };
+
+        let period = (period_end - period_start).num_seconds();
+        let elapsed = (now - period_start).num_seconds();
+
+        if period <= 0 || elapsed <= 0 || elapsed >= period {
+            return self.quantity;
+        }
+
+        (self.quantity as f64 * period as f64 / elapsed as f64).ceil() as i64
+    }
+}
+
+string_enum! {
+    pub enum IntervalUnit {
+        Day => "day",
+        Week => "week",
+        Month => "month",
+        Year => "year",
+    }
+}
+
+/// The amount due at the end of the billing period, if the usage keeps growing at the same rate
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct UsageProjection {
+    /// The usage recorded so far
+    pub usage: SubscriptionItemMetaResponse,
+    /// The usage extrapolated to the end of the period, see
+    /// `SubscriptionItemMetaResponse::projected_quantity`
+    pub projected_quantity: i64,
+    pub price: PriceResponse,
+    /// The price of `projected_quantity`
+    pub quote: Quote,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubscriptionItemPatchRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub quantity: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_immediately: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_prorations: Option<bool>,
 }
 
 pub struct SubscriptionItemFilters {
@@ -201,4 +272,129 @@ impl SubscriptionItems {
 
         Ok(response)
     }
+
+    /// Project the amount due at the end of the billing period from the current usage
+    ///
+    /// The usage recorded so far is extrapolated to the end of the billing period from the
+    /// elapsed fraction of the period, and priced with the price of the subscription item
+    /// (`price_id`).
+    ///
+    /// # Arguments
+    /// - subscription_item_id: The ID of the usage-based subscription item
+    ///
+    /// # Returns
+    /// - `anyhow::Result<UsageProjection, UsageProjectionError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscription_items::SubscriptionItems;
+    /// let subscription_items = SubscriptionItems::build(lemonsqueezy);
+    /// let projection = subscription_items.project_usage(1).await.unwrap();
+    /// println!("{} due on {}", projection.quote.total, projection.usage.period_end);
+    /// ```
+    pub async fn project_usage(
+        &self,
+        subscription_item_id: usize,
+    ) -> anyhow::Result<UsageProjection, UsageProjectionError> {
+        let item = self.retrieve(subscription_item_id).await?;
+        let price_id = item
+            .get_inner_response()
+            .ok_or(UsageProjectionError::MissingData("subscription item"))?
+            .price_id;
+
+        let price = Prices::build(self.api.clone())
+            .retrieve(price_id as usize)
+            .await?
+            .get_inner_response()
+            .cloned()
+            .ok_or(UsageProjectionError::MissingData("price"))?;
+
+        let usage = self
+            .current_usage(subscription_item_id)
+            .await?
+            .get_meta()
+            .clone();
+
+        let projected_quantity = usage.projected_quantity(Utc::now());
+        let quote = pricing::quote(&price, projected_quantity)?;
+
+        Ok(UsageProjection {
+            usage,
+            projected_quantity,
+            price,
+            quote,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(quantity: i64) -> SubscriptionItemMetaResponse {
+        SubscriptionItemMetaResponse {
+            period_start: "2023-06-01T00:00:00.000000Z".parse().unwrap(),
+            period_end: "2023-07-01T00:00:00.000000Z".parse().unwrap(),
+            quantity,
+            interval_unit: IntervalUnit::Month,
+            interval_quantity: 1,
+        }
+    }
+
+    fn at(now: &str) -> DateTime<Utc> {
+        now.parse().unwrap()
+    }
+
+    #[test]
+    fn projects_the_usage_to_the_end_of_the_period() {
+        assert_eq!(
+            usage(100).projected_quantity(at("2023-06-16T00:00:00Z")),
+            200
+        );
+        assert_eq!(usage(7).projected_quantity(at("2023-06-11T00:00:00Z")), 21);
+        assert_eq!(usage(0).projected_quantity(at("2023-06-16T00:00:00Z")), 0);
+    }
+
+    #[test]
+    fn keeps_the_usage_outside_of_the_period() {
+        assert_eq!(
+            usage(100).projected_quantity(at("2023-06-01T00:00:00Z")),
+            100
+        );
+        assert_eq!(
+            usage(100).projected_quantity(at("2023-05-01T00:00:00Z")),
+            100
+        );
+        assert_eq!(
+            usage(100).projected_quantity(at("2023-07-02T00:00:00Z")),
+            100
+        );
+    }
+
+    #[test]
+    fn deserializes_the_current_usage_meta() {
+        let meta: ResponseMeta<SubscriptionItemMetaResponse> = serde_json::from_value(json!({
+            "jsonapi": { "version": "1.0" },
+            "meta": {
+                "period_start": "2023-08-10T13:56:53+00:00",
+                "period_end": "2023-09-10T13:56:53+00:00",
+                "quantity": 5,
+                "interval_unit": "month",
+                "interval_quantity": 1
+            }
+        }))
+        .unwrap();
+
+        let usage = meta.get_meta();
+        assert_eq!(usage.quantity, 5);
+        assert_eq!(usage.interval_unit, IntervalUnit::Month);
+        assert!(usage.period_end.to_datetime().is_some());
+    }
+
+    #[test]
+    fn unknown_interval_units_deserialize() {
+        let unit: IntervalUnit = serde_json::from_value(json!("quarter")).unwrap();
+
+        assert_eq!(unit, IntervalUnit::Unknown("quarter".to_string()));
+    }
 }
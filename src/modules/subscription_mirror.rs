@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use tokio::sync::broadcast;
+
+use crate::subscriptions::{SubscriptionResponse, Subscriptions};
+use crate::utils::ToDateTime;
+use crate::webhook::WebhookSubscriptionData;
+
+/// Storage backing a `SubscriptionMirror`
+pub trait MirrorStorage: Send + Sync {
+    fn get(&self, subscription_id: i64) -> Option<SubscriptionResponse>;
+    fn put(&self, subscription_id: i64, subscription: SubscriptionResponse);
+    fn all(&self) -> Vec<(i64, SubscriptionResponse)>;
+}
+
+#[derive(Default, Debug)]
+pub struct InMemoryMirrorStorage {
+    subscriptions: RwLock<HashMap<i64, SubscriptionResponse>>,
+}
+
+impl MirrorStorage for InMemoryMirrorStorage {
+    fn get(&self, subscription_id: i64) -> Option<SubscriptionResponse> {
+        self.subscriptions
+            .read()
+            .unwrap()
+            .get(&subscription_id)
+            .cloned()
+    }
+
+    fn put(&self, subscription_id: i64, subscription: SubscriptionResponse) {
+        self.subscriptions
+            .write()
+            .unwrap()
+            .insert(subscription_id, subscription);
+    }
+
+    fn all(&self) -> Vec<(i64, SubscriptionResponse)> {
+        self.subscriptions
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(id, subscription)| (*id, subscription.clone()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriptionChange {
+    pub subscription_id: i64,
+    /// `None` if the subscription was not mirrored yet
+    pub previous: Option<SubscriptionResponse>,
+    pub current: SubscriptionResponse,
+}
+
+/// A local copy of subscriptions, kept in sync from webhooks and by polling the API
+///
+/// Updates older than the mirrored copy (by `updated_at`) are ignored, so webhooks delivered
+/// out of order can't overwrite newer state. An update with the same `updated_at` but a different
+/// content is applied, `updated_at` only has a one second resolution in some payloads and
+/// several updates can happen within the same second.
+pub struct SubscriptionMirror<S: MirrorStorage = InMemoryMirrorStorage> {
+    storage: S,
+    events: broadcast::Sender<SubscriptionChange>,
+    // serializes the read-compare-write of `ingest`
+    lock: Mutex<()>,
+}
+
+impl Default for SubscriptionMirror {
+    fn default() -> Self {
+        Self::new(InMemoryMirrorStorage::default())
+    }
+}
+
+impl<S: MirrorStorage> SubscriptionMirror<S> {
+    pub fn new(storage: S) -> Self {
+        let (events, _) = broadcast::channel(256);
+
+        Self {
+            storage,
+            events,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Subscribe to the changes applied to the mirror
+    pub fn subscribe(&self) -> broadcast::Receiver<SubscriptionChange> {
+        self.events.subscribe()
+    }
+
+    /// Retrieve the mirrored copy of a subscription
+    pub fn get(&self, subscription_id: i64) -> Option<SubscriptionResponse> {
+        self.storage.get(subscription_id)
+    }
+
+    /// Retrieve every mirrored subscription
+    pub fn all(&self) -> Vec<(i64, SubscriptionResponse)> {
+        self.storage.all()
+    }
+
+    /// Apply the payload of a `subscription_*` webhook
+    ///
+    /// # Returns
+    /// - `Option<SubscriptionChange>`, `None` if the payload was outdated, did not change anything
+    ///   or has a malformed ID
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscription_mirror::SubscriptionMirror;
+    /// use lemonsqueezy::webhook::WebhookSuscriptionEvent;
+    ///
+    /// let mirror = SubscriptionMirror::default();
+    /// let event: WebhookSuscriptionEvent<serde_json::Value> = serde_json::from_str(&body).unwrap();
+    /// mirror.ingest_webhook(&event.data);
+    /// ```
+    pub fn ingest_webhook(&self, data: &WebhookSubscriptionData) -> Option<SubscriptionChange> {
+        let subscription_id = data.id.parse().ok()?;
+
+        self.ingest(subscription_id, data.attributes.clone())
+    }
+
+    /// Apply a subscription fetched from the API or received from a webhook
+    ///
+    /// # Returns
+    /// - `Option<SubscriptionChange>`, `None` if the subscription was outdated or did not change anything
+    pub fn ingest(
+        &self,
+        subscription_id: i64,
+        subscription: SubscriptionResponse,
+    ) -> Option<SubscriptionChange> {
+        let _guard = self.lock.lock().unwrap();
+
+        let previous = self.storage.get(subscription_id);
+
+        if let Some(previous) = &previous {
+            if previous == &subscription {
+                return None;
+            }
+
+            let previous_updated_at = previous.updated_at.to_datetime();
+            let updated_at = subscription.updated_at.to_datetime();
+
+            if let (Some(previous_updated_at), Some(updated_at)) = (previous_updated_at, updated_at)
+            {
+                if updated_at < previous_updated_at {
+                    return None;
+                }
+            }
+        }
+
+        self.storage.put(subscription_id, subscription.clone());
+
+        let change = SubscriptionChange {
+            subscription_id,
+            previous,
+            current: subscription,
+        };
+
+        // no receivers is not an error
+        let _ = self.events.send(change.clone());
+
+        Some(change)
+    }
+
+    /// Fetch every subscription of a store and apply them to the mirror
+    ///
+    /// # Arguments
+    /// - subscriptions: The subscriptions API
+    /// - store_id: The store to reconcile the subscriptions of
+    ///
+    /// # Returns
+    /// - `anyhow::Result<Vec<SubscriptionChange>, crate::errors::NetworkError>` object
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscription_mirror::SubscriptionMirror;
+    /// use lemonsqueezy::subscriptions::Subscriptions;
+    ///
+    /// let mirror = SubscriptionMirror::default();
+    /// let changes = mirror.reconcile(&Subscriptions::build(lemonsqueezy), 1).await.unwrap();
+    /// ```
+    pub async fn reconcile(
+        &self,
+        subscriptions: &Subscriptions,
+        store_id: i64,
+    ) -> anyhow::Result<Vec<SubscriptionChange>, crate::errors::NetworkError> {
+        let response = subscriptions
+            .api
+            .get_all_pages::<SubscriptionResponse>(&format!(
+                "/v1/subscriptions?filter[store_id]={}",
+                store_id
+            ))
+            .await?;
+
+        let changes = response
+            .into_iter()
+            .filter_map(|data| {
+                let subscription_id = data.id.parse().ok()?;
+
+                self.ingest(subscription_id, data.attributes)
+            })
+            .collect();
+
+        Ok(changes)
+    }
+
+    /// Reconcile the subscriptions of a store every `period`, forever
+    ///
+    /// Failed reconciliations are passed to `on_error` and retried on the next tick.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use lemonsqueezy::subscription_mirror::SubscriptionMirror;
+    /// use lemonsqueezy::subscriptions::Subscriptions;
+    ///
+    /// let mirror = Arc::new(SubscriptionMirror::default());
+    /// tokio::spawn(mirror.clone().run(
+    ///     Subscriptions::build(lemonsqueezy),
+    ///     1,
+    ///     Duration::from_secs(600),
+    ///     |e| eprintln!("Failed to reconcile the subscriptions: {}", e),
+    /// ));
+    /// ```
+    pub async fn run(
+        self: Arc<Self>,
+        subscriptions: Subscriptions,
+        store_id: i64,
+        period: std::time::Duration,
+        mut on_error: impl FnMut(crate::errors::NetworkError) + Send,
+    ) {
+        let mut interval = tokio::time::interval(period);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.reconcile(&subscriptions, store_id).await {
+                on_error(e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subscriptions::SubscriptionStatus;
+    use crate::webhook::WebhookSuscriptionEvent;
+
+    fn relationship(name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "links": {
+                "related": format!("https://api.lemonsqueezy.com/v1/subscriptions/1/{}", name),
+                "self": format!("https://api.lemonsqueezy.com/v1/subscriptions/1/relationships/{}", name)
+            }
+        })
+    }
+
+    /// A `subscription_updated` webhook, as sent by Lemon Squeezy
+    fn subscription_updated(status: &str, updated_at: &str) -> serde_json::Value {
+        serde_json::json!({
+            "meta": {
+                "event_name": "subscription_updated",
+                "custom_data": { "customer_id": 25 }
+            },
+            "data": {
+                "type": "subscriptions",
+                "id": "1",
+                "attributes": {
+                    "store_id": 1,
+                    "customer_id": 1,
+                    "order_id": 1,
+                    "order_item_id": 1,
+                    "product_id": 1,
+                    "variant_id": 1,
+                    "product_name": "Lemonade",
+                    "variant_name": "Citrus Blast",
+                    "user_name": "John Doe",
+                    "user_email": "johndoe@example.com",
+                    "status": status,
+                    "status_formatted": "Active",
+                    "card_brand": "visa",
+                    "card_last_four": "4242",
+                    "pause": null,
+                    "cancelled": false,
+                    "trial_ends_at": null,
+                    "billing_anchor": 12,
+                    "first_subscription_item": {
+                        "id": 1,
+                        "subscription_id": 1,
+                        "price_id": 1,
+                        "quantity": 5,
+                        "is_usage_based": false,
+                        "created_at": "2021-08-11T13:47:28.000000Z",
+                        "updated_at": "2021-08-11T13:47:28.000000Z"
+                    },
+                    "urls": {
+                        "update_payment_method": "https://my-store.lemonsqueezy.com/subscription/1/payment-details?expires=1666869343&signature=9985e3bf9007840aeb3951412be475abc17439c449c1af3e56e08e45e1345413",
+                        "customer_portal": "https://my-store.lemonsqueezy.com/billing?expires=1666869343&signature=82ae290ceac8edd4190c82825dd73a8743346d894a8ddbc4898b97eb96d105a5"
+                    },
+                    "renews_at": "2021-09-12T13:47:27.000000Z",
+                    "ends_at": null,
+                    "created_at": "2021-08-11T13:47:27.000000Z",
+                    "updated_at": updated_at,
+                    "test_mode": false
+                },
+                "relationships": {
+                    "store": relationship("store"),
+                    "customer": relationship("customer"),
+                    "order": relationship("order"),
+                    "order-item": relationship("order-item"),
+                    "product": relationship("product"),
+                    "variant": relationship("variant"),
+                    "subscription-items": relationship("subscription-items"),
+                    "subscription-invoices": relationship("subscription-invoices")
+                },
+                "links": {
+                    "self": "https://api.lemonsqueezy.com/v1/subscriptions/1"
+                }
+            }
+        })
+    }
+
+    fn event(status: &str, updated_at: &str) -> WebhookSuscriptionEvent<serde_json::Value> {
+        serde_json::from_value(subscription_updated(status, updated_at)).unwrap()
+    }
+
+    #[test]
+    fn ingests_a_real_webhook_payload() {
+        let mirror = SubscriptionMirror::default();
+        let event = event("active", "2021-08-11T13:54:19.000000Z");
+
+        assert_eq!(event.data.id, "1");
+        assert!(event.data.relationships.contains_key("subscription-items"));
+
+        let change = mirror.ingest_webhook(&event.data).unwrap();
+
+        assert_eq!(change.subscription_id, 1);
+        assert_eq!(change.previous, None);
+        assert_eq!(change.current.status, SubscriptionStatus::Active);
+        assert_eq!(mirror.get(1).unwrap().first_subscription_item.quantity, 5);
+    }
+
+    #[test]
+    fn applies_newer_updates_and_notifies_subscribers() {
+        let mirror = SubscriptionMirror::default();
+        let mut events = mirror.subscribe();
+
+        mirror.ingest_webhook(&event("active", "2021-08-11T13:54:19.000000Z").data);
+        let change = mirror
+            .ingest_webhook(&event("past_due", "2021-09-12T14:00:00.000000Z").data)
+            .unwrap();
+
+        assert_eq!(change.previous.unwrap().status, SubscriptionStatus::Active);
+        assert_eq!(change.current.status, SubscriptionStatus::PastDue);
+
+        assert_eq!(
+            events.try_recv().unwrap().current.status,
+            SubscriptionStatus::Active
+        );
+        assert_eq!(
+            events.try_recv().unwrap().current.status,
+            SubscriptionStatus::PastDue
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn ignores_out_of_order_deliveries() {
+        let mirror = SubscriptionMirror::default();
+        let mut events = mirror.subscribe();
+
+        mirror.ingest_webhook(&event("past_due", "2021-09-12T14:00:00.000000Z").data);
+
+        assert_eq!(
+            mirror.ingest_webhook(&event("active", "2021-08-11T13:54:19.000000Z").data),
+            None
+        );
+        assert_eq!(mirror.get(1).unwrap().status, SubscriptionStatus::PastDue);
+
+        events.try_recv().unwrap();
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn applies_changes_with_the_same_updated_at() {
+        let mirror = SubscriptionMirror::default();
+
+        mirror.ingest_webhook(&event("active", "2021-08-11T13:54:19.000000Z").data);
+        let change = mirror
+            .ingest_webhook(&event("cancelled", "2021-08-11T13:54:19.000000Z").data)
+            .unwrap();
+
+        assert_eq!(change.previous.unwrap().status, SubscriptionStatus::Active);
+        assert_eq!(mirror.get(1).unwrap().status, SubscriptionStatus::Cancelled);
+    }
+
+    #[test]
+    fn ignores_redeliveries() {
+        let mirror = SubscriptionMirror::default();
+        let event = event("active", "2021-08-11T13:54:19.000000Z");
+
+        assert!(mirror.ingest_webhook(&event.data).is_some());
+        assert_eq!(mirror.ingest_webhook(&event.data), None);
+        assert_eq!(mirror.all().len(), 1);
+    }
+
+    #[test]
+    fn ignores_malformed_ids() {
+        let mirror = SubscriptionMirror::default();
+        let mut event = event("active", "2021-08-11T13:54:19.000000Z");
+        event.data.id = "sub_1".to_string();
+
+        assert_eq!(mirror.ingest_webhook(&event.data), None);
+        assert!(mirror.all().is_empty());
+    }
+}
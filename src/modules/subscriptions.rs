@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubscriptionResponse {
@@ -15,39 +15,80 @@ pub struct SubscriptionResponse {
     pub variant_name: String,
     pub user_name: String,
     pub user_email: String,
-    pub status: String,
+    pub status: SubscriptionStatus,
     pub status_formatted: String,
     pub card_brand: String,
     pub card_last_four: String,
-    pub pause: Option<Value>,
+    pub pause: Option<SubscriptionPause>,
     pub cancelled: bool,
-    pub trial_ends_at: Option<Value>,
+    pub trial_ends_at: Option<Timestamp>,
     pub billing_anchor: i64,
     pub first_subscription_item: FirstSubscriptionItem,
     pub urls: Urls,
-    pub renews_at: String,
-    pub ends_at: Value,
-    pub created_at: String,
-    pub updated_at: String,
+    pub renews_at: Timestamp,
+    pub ends_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
     pub test_mode: bool,
 }
 
+string_enum! {
+    pub enum SubscriptionStatus {
+        OnTrial => "on_trial",
+        Active => "active",
+        Paused => "paused",
+        PastDue => "past_due",
+        Unpaid => "unpaid",
+        Cancelled => "cancelled",
+        Expired => "expired",
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SubscriptionPatchRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub product_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub variant_id: Option<i64>,
+    /// `Some(None)` unpauses the subscription
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub pause: Option<Value>,
+    pub pause: Option<Option<SubscriptionPause>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cancelled: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub billing_anchor: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub invoice_immediately: Option<String>,
+    pub invoice_immediately: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub disable_prorations: Option<String>,
+    pub disable_prorations: Option<bool>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionPause {
+    pub mode: PauseMode,
+    pub resumes_at: Option<Timestamp>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PauseMode {
+    /// The customer can't use the product while the subscription is paused
+    #[default]
+    Void,
+    /// The product is offered for free while the subscription is paused
+    Free,
+}
+
+/// How a plan change is billed
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProrationBehavior {
+    /// Prorate the change on the next invoice
+    #[default]
+    Prorate,
+    /// Prorate the change and invoice it immediately
+    InvoiceImmediately,
+    /// Charge the full price of the new plan on the next renewal, without prorating
+    DisableProrations,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -56,13 +97,20 @@ pub struct FirstSubscriptionItem {
     pub subscription_id: i64,
     pub price_id: i64,
     pub quantity: i64,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Urls {
+    /// Signed URL to update the payment method, valid for 24 hours
     pub update_payment_method: String,
+    /// Signed URL to the customer portal, valid for 24 hours
+    #[serde(default)]
+    pub customer_portal: Option<String>,
+    /// Signed URL to the customer portal page of this subscription, valid for 24 hours
+    #[serde(default)]
+    pub customer_portal_update_subscription: Option<String>,
 }
 
 pub struct SubscriptionFilters {
@@ -214,13 +262,253 @@ impl Subscriptions {
         Ok(response)
     }
 
+    /// Get a fresh signed URL to the customer portal of a Subscription
+    ///
+    /// The URLs returned by the API expire after 24 hours, so the subscription is fetched again,
+    /// bypassing the cache.
+    ///
+    /// # Arguments
+    /// * `subscription_id` - The subscription id
+    ///
+    /// # Returns
+    /// anyhow::Result<Option<String>, crate::errors::NetworkError>
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscriptions::Subscriptions;
+    /// let subscriptions = Subscriptions::build(lemonsqueezy);
+    /// let url = subscriptions.customer_portal_url(123).await.unwrap();
+    /// ```
+    pub async fn customer_portal_url(
+        &self,
+        subscription_id: usize,
+    ) -> anyhow::Result<Option<String>, crate::errors::NetworkError> {
+        let response = self
+            .api
+            .get_uncached::<Response<SubscriptionResponse>>(&format!(
+                "/v1/subscriptions/{}",
+                subscription_id
+            ))
+            .await?;
+
+        Ok(response
+            .get_inner_response()
+            .and_then(|subscription| subscription.urls.customer_portal.clone()))
+    }
+
+    /// Get a fresh signed URL to update the payment method of a Subscription
+    ///
+    /// The URLs returned by the API expire after 24 hours, so the subscription is fetched again,
+    /// bypassing the cache.
+    ///
+    /// # Arguments
+    /// * `subscription_id` - The subscription id
+    ///
+    /// # Returns
+    /// anyhow::Result<Option<String>, crate::errors::NetworkError>
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscriptions::Subscriptions;
+    /// let subscriptions = Subscriptions::build(lemonsqueezy);
+    /// let url = subscriptions.update_payment_method_url(123).await.unwrap();
+    /// ```
+    pub async fn update_payment_method_url(
+        &self,
+        subscription_id: usize,
+    ) -> anyhow::Result<Option<String>, crate::errors::NetworkError> {
+        let response = self
+            .api
+            .get_uncached::<Response<SubscriptionResponse>>(&format!(
+                "/v1/subscriptions/{}",
+                subscription_id
+            ))
+            .await?;
+
+        Ok(response
+            .get_inner_response()
+            .map(|subscription| subscription.urls.update_payment_method.clone()))
+    }
+
+    /// Pause a Subscription
+    ///
+    /// # Arguments
+    /// * `subscription_id` - The subscription id
+    /// * `mode` - Whether the product is unavailable (`Void`) or free (`Free`) while paused
+    /// * `resumes_at` - Optionally, the date at which the subscription resumes
+    ///
+    /// # Returns
+    /// anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError>
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscriptions::{PauseMode, Subscriptions};
+    /// let subscriptions = Subscriptions::build(lemonsqueezy);
+    /// let subscription = subscriptions.pause(123, PauseMode::Void, None).await.unwrap();
+    /// ```
+    ///
+    /// Read More: https://docs.lemonsqueezy.com/api/subscriptions#update-a-subscription
+    pub async fn pause(
+        &self,
+        subscription_id: usize,
+        mode: PauseMode,
+        resumes_at: Option<Timestamp>,
+    ) -> anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError> {
+        self.patch_attributes(
+            subscription_id,
+            SubscriptionPatchRequest {
+                pause: Some(Some(SubscriptionPause { mode, resumes_at })),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Unpause a paused Subscription
+    ///
+    /// # Arguments
+    /// * `subscription_id` - The subscription id
+    ///
+    /// # Returns
+    /// anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError>
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscriptions::Subscriptions;
+    /// let subscriptions = Subscriptions::build(lemonsqueezy);
+    /// let subscription = subscriptions.unpause(123).await.unwrap();
+    /// ```
+    ///
+    /// Read More: https://docs.lemonsqueezy.com/api/subscriptions#update-a-subscription
+    pub async fn unpause(
+        &self,
+        subscription_id: usize,
+    ) -> anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError> {
+        self.patch_attributes(
+            subscription_id,
+            SubscriptionPatchRequest {
+                pause: Some(None),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Resume a cancelled Subscription, before the end of its grace period
+    ///
+    /// # Arguments
+    /// * `subscription_id` - The subscription id
+    ///
+    /// # Returns
+    /// anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError>
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscriptions::Subscriptions;
+    /// let subscriptions = Subscriptions::build(lemonsqueezy);
+    /// let subscription = subscriptions.resume(123).await.unwrap();
+    /// ```
+    ///
+    /// Read More: https://docs.lemonsqueezy.com/api/subscriptions#update-a-subscription
+    pub async fn resume(
+        &self,
+        subscription_id: usize,
+    ) -> anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError> {
+        self.patch_attributes(
+            subscription_id,
+            SubscriptionPatchRequest {
+                cancelled: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Move a Subscription to another variant (plan)
+    ///
+    /// # Arguments
+    /// * `subscription_id` - The subscription id
+    /// * `variant_id` - The id of the variant to move to
+    /// * `proration` - How the change is billed
+    ///
+    /// # Returns
+    /// anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError>
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscriptions::{ProrationBehavior, Subscriptions};
+    /// let subscriptions = Subscriptions::build(lemonsqueezy);
+    /// let subscription = subscriptions
+    ///     .change_variant(123, 456, ProrationBehavior::InvoiceImmediately)
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    ///
+    /// Read More: https://docs.lemonsqueezy.com/api/subscriptions#update-a-subscription
+    pub async fn change_variant(
+        &self,
+        subscription_id: usize,
+        variant_id: i64,
+        proration: ProrationBehavior,
+    ) -> anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError> {
+        let (invoice_immediately, disable_prorations) = match proration {
+            ProrationBehavior::Prorate => (None, None),
+            ProrationBehavior::InvoiceImmediately => (Some(true), None),
+            ProrationBehavior::DisableProrations => (None, Some(true)),
+        };
+
+        self.patch_attributes(
+            subscription_id,
+            SubscriptionPatchRequest {
+                variant_id: Some(variant_id),
+                invoice_immediately,
+                disable_prorations,
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Change the billing day of a Subscription
+    ///
+    /// # Arguments
+    /// * `subscription_id` - The subscription id
+    /// * `billing_anchor` - The day of the month (1-31) the subscription renews on,
+    ///   or `0` to renew from today
+    ///
+    /// # Returns
+    /// anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError>
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::subscriptions::Subscriptions;
+    /// let subscriptions = Subscriptions::build(lemonsqueezy);
+    /// let subscription = subscriptions.set_billing_anchor(123, 1).await.unwrap();
+    /// ```
+    ///
+    /// Read More: https://docs.lemonsqueezy.com/api/subscriptions#update-a-subscription
+    pub async fn set_billing_anchor(
+        &self,
+        subscription_id: usize,
+        billing_anchor: i64,
+    ) -> anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError> {
+        self.patch_attributes(
+            subscription_id,
+            SubscriptionPatchRequest {
+                billing_anchor: Some(billing_anchor),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
     /// Cancel a Subscription
     ///
     /// # Arguments
     /// * `subscription_id` - The subscription id
     ///
     /// # Returns
-    /// anyhow::Result<Value, crate::errors::NetworkError>
+    /// anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError>
     ///
     /// # Example
     /// ```rust
@@ -229,11 +517,11 @@ impl Subscriptions {
     /// let subscriptions = subscriptions.cancel(123).await.unwrap();
     /// ```
     ///
-    /// Read More: https://docs.lemonsqueezy.com/api/subscriptions#update-a-subscription
+    /// Read More: https://docs.lemonsqueezy.com/api/subscriptions#cancel-a-subscription
     pub async fn cancel(
         &self,
         subscription_id: usize,
-    ) -> anyhow::Result<serde_json::Value, crate::errors::NetworkError> {
+    ) -> anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError> {
         let response = self
             .api
             .delete(&format!("/v1/subscriptions/{}", subscription_id))
@@ -241,4 +529,30 @@ impl Subscriptions {
 
         Ok(response)
     }
+
+    async fn patch_attributes(
+        &self,
+        subscription_id: usize,
+        attributes: SubscriptionPatchRequest,
+    ) -> anyhow::Result<Response<SubscriptionResponse>, crate::errors::NetworkError> {
+        let body = json!({
+            "data": {
+                "type": "subscriptions",
+                "id": subscription_id.to_string(),
+                "attributes": attributes,
+            }
+        });
+
+        let reqwest_body = reqwest::Body::from(serde_json::to_string(&body).unwrap());
+
+        let response = self
+            .api
+            .patch_typed(
+                &format!("/v1/subscriptions/{}", subscription_id),
+                reqwest_body,
+            )
+            .await?;
+
+        Ok(response)
+    }
 }
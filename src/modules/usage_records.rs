@@ -1,14 +1,14 @@
+use reqwest::header::{HeaderMap, HeaderValue};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UsageRecordResponse {
     pub subscription_item_id: i64,
     pub quantity: i64,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -16,6 +16,25 @@ pub struct CreateUsageRecord {
     pub data: CreateUsageRecordData,
 }
 
+impl CreateUsageRecord {
+    pub fn new(subscription_item_id: i64, quantity: i64, action: UsageAction) -> Self {
+        Self {
+            data: CreateUsageRecordData {
+                r#type: "usage-records".to_string(),
+                attributes: CreateUsageRecordAttributes { quantity, action },
+                relationships: CreateUsageRecordRelationships {
+                    subscription_item: CreateUsageRecordSubscriptionItem {
+                        data: CreateUsageRecordSubscriptionItemData {
+                            r#type: "subscription-items".to_string(),
+                            id: subscription_item_id.to_string(),
+                        },
+                    },
+                },
+            },
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateUsageRecordData {
     pub r#type: String,
@@ -26,7 +45,17 @@ pub struct CreateUsageRecordData {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateUsageRecordAttributes {
     pub quantity: i64,
-    pub action: String,
+    pub action: UsageAction,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageAction {
+    /// Add the quantity to the usage of the current billing period
+    #[default]
+    Increment,
+    /// Replace the usage of the current billing period with the quantity
+    Set,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -131,26 +160,111 @@ impl UsageRecords {
         Ok(response)
     }
 
-    /// Update a usage record
+    /// Create a usage record
+    ///
     /// # Arguments
-    /// - usage_record_id: The ID of the usage record to update
     /// - data: The usage record data
     ///
     /// # Returns
-    /// - `anyhow::Result<Value, crate::errors::NetworkError>` object
+    /// - `anyhow::Result<Response<UsageRecordResponse>, crate::errors::NetworkError>` object
     ///
     /// # Example
     /// ```
-    /// use lemonsqueezy::usage_records::UsageRecords;
+    /// use lemonsqueezy::usage_records::{CreateUsageRecord, UsageAction, UsageRecords};
     /// let usage_records = UsageRecords::build(lemonsqueezy);
-    /// let usage_record = usage_records.create(usage_record_data).await;
+    /// let usage_record = usage_records.create(CreateUsageRecord::new(1, 10, UsageAction::Increment)).await;
     /// ```
     pub async fn create(
         &self,
         data: CreateUsageRecord,
-    ) -> anyhow::Result<Value, crate::errors::NetworkError> {
+    ) -> anyhow::Result<Response<UsageRecordResponse>, crate::errors::NetworkError> {
         let response = self.api.post("/v1/usage-records", data).await?;
 
         Ok(response)
     }
+
+    /// Create a usage record, sending a key in the `Idempotency-Key` header
+    ///
+    /// The header is best-effort only: Lemon Squeezy does not document honoring it, so retrying
+    /// after a lost response may count the usage twice. Pass the same key when retrying the same
+    /// usage record, in case it is honored.
+    ///
+    /// # Arguments
+    /// - data: The usage record data
+    /// - idempotency_key: The key identifying this usage record, see `new_idempotency_key`
+    ///
+    /// # Returns
+    /// - `anyhow::Result<Response<UsageRecordResponse>, crate::errors::NetworkError>` object, an
+    ///   error if the key is not a valid header value, the response status is not a success
+    ///   (`StatusCodeError`) or the response has no usage record (`MissingData`)
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::usage_records::{CreateUsageRecord, UsageAction, UsageRecords};
+    /// let usage_records = UsageRecords::build(lemonsqueezy);
+    /// let key = UsageRecords::new_idempotency_key();
+    /// let data = CreateUsageRecord::new(1, 10, UsageAction::Increment);
+    /// let usage_record = usage_records.create_with_idempotency_key(data, &key).await;
+    /// ```
+    pub async fn create_with_idempotency_key(
+        &self,
+        data: CreateUsageRecord,
+        idempotency_key: &str,
+    ) -> anyhow::Result<Response<UsageRecordResponse>, crate::errors::NetworkError> {
+        let mut headers = HeaderMap::new();
+        headers.append("Idempotency-Key", HeaderValue::from_str(idempotency_key)?);
+
+        let response: Response<UsageRecordResponse> = self
+            .api
+            .post_with_headers("/v1/usage-records", headers, data)
+            .await?;
+
+        if response.get_data().is_none() {
+            return Err(crate::errors::NetworkError::MissingData);
+        }
+
+        Ok(response)
+    }
+
+    /// Record usage for a subscription item
+    ///
+    /// Generate the idempotency key once with `new_idempotency_key` and pass the same key when
+    /// retrying the same record, e.g. after a timeout. The key is best-effort only, see
+    /// `create_with_idempotency_key`: a retry may still count the usage twice.
+    ///
+    /// # Arguments
+    /// - subscription_item_id: The ID of the subscription item the usage belongs to
+    /// - quantity: The number of units
+    /// - action: Whether the quantity is added to (`Increment`) or replaces (`Set`) the current usage
+    /// - idempotency_key: The key identifying this usage record, sent in the `Idempotency-Key` header
+    ///
+    /// # Returns
+    /// - `anyhow::Result<Response<UsageRecordResponse>, crate::errors::NetworkError>` object, an
+    ///   error on a response status that is not a success
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::usage_records::{UsageAction, UsageRecords};
+    /// let usage_records = UsageRecords::build(lemonsqueezy);
+    /// let key = UsageRecords::new_idempotency_key();
+    /// let usage_record = usage_records.record(1, 10, UsageAction::Increment, &key).await;
+    /// ```
+    pub async fn record(
+        &self,
+        subscription_item_id: i64,
+        quantity: i64,
+        action: UsageAction,
+        idempotency_key: &str,
+    ) -> anyhow::Result<Response<UsageRecordResponse>, crate::errors::NetworkError> {
+        self.create_with_idempotency_key(
+            CreateUsageRecord::new(subscription_item_id, quantity, action),
+            idempotency_key,
+        )
+        .await
+    }
+
+    /// Generate a new random idempotency key
+    pub fn new_idempotency_key() -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
 }
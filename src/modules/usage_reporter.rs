@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::errors::NetworkError;
+use crate::usage_records::{CreateUsageRecord, UsageAction, UsageRecordResponse, UsageRecords};
+use crate::utils::Response;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UsageReporterConfig {
+    /// How often the background task started by `UsageReporter::spawn` flushes
+    pub flush_interval: Duration,
+    /// Flush as soon as this many units are pending, across all subscription items. After a
+    /// failed flush, the threshold doesn't trigger another one before `flush_interval`.
+    pub flush_threshold: i64,
+}
+
+impl Default for UsageReporterConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval: Duration::from_secs(60),
+            flush_threshold: 10_000,
+        }
+    }
+}
+
+/// A batch of usage being reported, retried with the same idempotency key until it goes through
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Batch {
+    quantity: i64,
+    idempotency_key: String,
+}
+
+/// What became of a batch sent to the API
+#[derive(Debug)]
+enum Outcome {
+    Reported,
+    /// The API refused the batch, retrying it would fail the same way
+    Rejected(NetworkError),
+    /// The batch may go through on a retry, e.g. after a rate limit or a network error
+    Failed(NetworkError),
+}
+
+impl Outcome {
+    /// A batch only counts as reported if the API returned the created usage record, an error
+    /// body deserializes into a `Response` without data
+    fn of(result: Result<Response<UsageRecordResponse>, NetworkError>) -> Self {
+        match result {
+            Ok(response) if response.get_data().is_some() => Outcome::Reported,
+            Ok(_) => Outcome::Failed(NetworkError::MissingData),
+            Err(NetworkError::StatusCodeError(status))
+                if status.is_client_error()
+                    && status != StatusCode::TOO_MANY_REQUESTS
+                    && status != StatusCode::REQUEST_TIMEOUT =>
+            {
+                Outcome::Rejected(NetworkError::StatusCodeError(status))
+            }
+            Err(e) => Outcome::Failed(e),
+        }
+    }
+}
+
+/// The usage not reported yet: accumulating (`pending`) or in a batch being reported
+/// (`in_flight`), and the usage the API refused (`rejected`)
+#[derive(Debug, Default)]
+struct UsageBuffer {
+    pending: HashMap<i64, i64>,
+    in_flight: HashMap<i64, Batch>,
+    rejected: HashMap<i64, i64>,
+}
+
+impl UsageBuffer {
+    /// Add usage to a subscription item and return the number of pending units, across all items
+    fn add(&mut self, subscription_item_id: i64, quantity: i64) -> i64 {
+        *self.pending.entry(subscription_item_id).or_insert(0) += quantity;
+
+        self.pending.values().sum()
+    }
+
+    /// Start a batch for every item with pending usage and no batch yet, and return all the
+    /// batches to report
+    ///
+    /// An item with a batch waiting to be retried keeps accumulating until the batch goes
+    /// through, so a retry never includes usage that may already have been reported.
+    fn batches(&mut self) -> Vec<(i64, Batch)> {
+        let in_flight = &mut self.in_flight;
+
+        self.pending.retain(|subscription_item_id, quantity| {
+            if in_flight.contains_key(subscription_item_id) {
+                return true;
+            }
+
+            if *quantity != 0 {
+                in_flight.insert(
+                    *subscription_item_id,
+                    Batch {
+                        quantity: *quantity,
+                        idempotency_key: uuid::Uuid::new_v4().to_string(),
+                    },
+                );
+            }
+
+            false
+        });
+
+        self.in_flight
+            .iter()
+            .map(|(subscription_item_id, batch)| (*subscription_item_id, batch.clone()))
+            .collect()
+    }
+
+    /// Drop the batch of an item once it was reported
+    fn reported(&mut self, subscription_item_id: i64) {
+        self.in_flight.remove(&subscription_item_id);
+    }
+
+    /// Set aside the batch of an item the API refused, so the usage recorded since can be reported
+    fn rejected(&mut self, subscription_item_id: i64) {
+        if let Some(batch) = self.in_flight.remove(&subscription_item_id) {
+            *self.rejected.entry(subscription_item_id).or_insert(0) += batch.quantity;
+        }
+    }
+
+    /// The units not reported yet per item, pending and in flight
+    fn unreported(&self) -> HashMap<i64, i64> {
+        let mut unreported = self.pending.clone();
+
+        for (subscription_item_id, batch) in &self.in_flight {
+            *unreported.entry(*subscription_item_id).or_insert(0) += batch.quantity;
+        }
+
+        unreported.retain(|_, quantity| *quantity != 0);
+        unreported
+    }
+}
+
+/// Accumulates usage in memory and reports it in batches with `action: increment`
+///
+/// Every batch gets its own idempotency key, sent in the `Idempotency-Key` header. A batch that
+/// fails to be reported is retried as-is, with the same key, on the next flush; usage recorded in
+/// the meantime for the same subscription item waits for it. Lemon Squeezy does not document
+/// honoring `Idempotency-Key` though: if a batch was applied but its response was lost, the
+/// retry may be counted twice.
+///
+/// A batch the API refuses with a client error (other than 408 and 429) is not retried, it is set
+/// aside and listed by `rejected`.
+pub struct UsageReporter {
+    usage_records: UsageRecords,
+    config: UsageReporterConfig,
+    buffer: Mutex<UsageBuffer>,
+    // serializes the flushes, so a batch is never reported twice at the same time
+    flush_lock: tokio::sync::Mutex<()>,
+    // when the last flush failed, the threshold doesn't trigger a flush before `flush_interval`
+    failed_at: Mutex<Option<Instant>>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl UsageReporter {
+    pub fn build(usage_records: UsageRecords, config: UsageReporterConfig) -> Self {
+        let (shutdown, _) = watch::channel(false);
+
+        Self {
+            usage_records,
+            config,
+            buffer: Mutex::new(UsageBuffer::default()),
+            flush_lock: tokio::sync::Mutex::new(()),
+            failed_at: Mutex::new(None),
+            shutdown,
+        }
+    }
+
+    /// Record usage for a subscription item, flushing if the threshold is reached
+    ///
+    /// The usage is buffered even if the flush fails, it is retried on the next flush. Call
+    /// `flush` to get the error of a flush.
+    ///
+    /// # Arguments
+    /// - subscription_item_id: The ID of the subscription item the usage belongs to
+    /// - quantity: The number of units to add
+    ///
+    /// # Example
+    /// ```ignore
+    /// use lemonsqueezy::usage_records::UsageRecords;
+    /// use lemonsqueezy::usage_reporter::{UsageReporter, UsageReporterConfig};
+    ///
+    /// let reporter = UsageReporter::build(UsageRecords::build(lemonsqueezy), UsageReporterConfig::default());
+    /// reporter.add(1, 3).await;
+    /// ```
+    pub async fn add(&self, subscription_item_id: i64, quantity: i64) {
+        let pending_total = self
+            .buffer
+            .lock()
+            .unwrap()
+            .add(subscription_item_id, quantity);
+
+        let backing_off = self
+            .failed_at
+            .lock()
+            .unwrap()
+            .is_some_and(|failed_at| failed_at.elapsed() < self.config.flush_interval);
+
+        if pending_total >= self.config.flush_threshold && !backing_off {
+            let _ = self.flush().await;
+        }
+    }
+
+    /// The number of units not reported yet per subscription item, including the batches
+    /// waiting to be retried
+    pub fn pending(&self) -> HashMap<i64, i64> {
+        self.buffer.lock().unwrap().unreported()
+    }
+
+    /// The number of units the API refused per subscription item, they are not retried
+    pub fn rejected(&self) -> HashMap<i64, i64> {
+        self.buffer.lock().unwrap().rejected.clone()
+    }
+
+    /// Report the pending usage
+    ///
+    /// Every batch is attempted, if some fail they are kept for the next flush, the ones the API
+    /// refused are set aside, and the first error is returned.
+    ///
+    /// # Returns
+    /// - `anyhow::Result<(), crate::errors::NetworkError>` object
+    pub async fn flush(&self) -> anyhow::Result<(), crate::errors::NetworkError> {
+        let _flush = self.flush_lock.lock().await;
+
+        let batches = self.buffer.lock().unwrap().batches();
+        let mut error = None;
+        let mut failed = false;
+
+        for (subscription_item_id, batch) in batches {
+            let data = CreateUsageRecord::new(
+                subscription_item_id,
+                batch.quantity,
+                UsageAction::Increment,
+            );
+
+            let result = self
+                .usage_records
+                .create_with_idempotency_key(data, &batch.idempotency_key)
+                .await;
+
+            match Outcome::of(result) {
+                Outcome::Reported => self.buffer.lock().unwrap().reported(subscription_item_id),
+                Outcome::Rejected(e) => {
+                    self.buffer.lock().unwrap().rejected(subscription_item_id);
+                    error.get_or_insert(e);
+                }
+                Outcome::Failed(e) => {
+                    failed = true;
+                    error.get_or_insert(e);
+                }
+            }
+        }
+
+        *self.failed_at.lock().unwrap() = failed.then(Instant::now);
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Flush every `flush_interval` in the background, until `shutdown` is called
+    ///
+    /// Failed flushes are retried on the next tick.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use std::sync::Arc;
+    /// use lemonsqueezy::usage_records::UsageRecords;
+    /// use lemonsqueezy::usage_reporter::{UsageReporter, UsageReporterConfig};
+    ///
+    /// let reporter = Arc::new(UsageReporter::build(UsageRecords::build(lemonsqueezy), UsageReporterConfig::default()));
+    /// let handle = reporter.clone().spawn();
+    /// // ...
+    /// reporter.shutdown().await.unwrap();
+    /// handle.await.unwrap();
+    /// ```
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        let mut shutdown = self.shutdown.subscribe();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.flush_interval);
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        let _ = self.flush().await;
+                    }
+                    _ = shutdown.changed() => break,
+                }
+            }
+        })
+    }
+
+    /// Stop the background task started by `spawn` and report the remaining usage
+    ///
+    /// # Returns
+    /// - `anyhow::Result<(), crate::errors::NetworkError>` object, usage that could not be
+    ///   reported is kept and can be flushed again
+    pub async fn shutdown(&self) -> anyhow::Result<(), crate::errors::NetworkError> {
+        self.shutdown.send_replace(true);
+
+        self.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_accumulates_per_item_and_returns_the_pending_total() {
+        let mut buffer = UsageBuffer::default();
+
+        assert_eq!(buffer.add(1, 3), 3);
+        assert_eq!(buffer.add(2, 4), 7);
+        assert_eq!(buffer.add(1, 5), 12);
+        assert_eq!(buffer.unreported(), HashMap::from([(1, 8), (2, 4)]));
+    }
+
+    #[test]
+    fn batches_move_pending_usage_in_flight() {
+        let mut buffer = UsageBuffer::default();
+        buffer.add(1, 3);
+        buffer.add(2, 0);
+
+        let batches = buffer.batches();
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].0, 1);
+        assert_eq!(batches[0].1.quantity, 3);
+        assert!(buffer.pending.is_empty());
+        assert_eq!(buffer.unreported(), HashMap::from([(1, 3)]));
+    }
+
+    #[test]
+    fn reported_batches_are_dropped() {
+        let mut buffer = UsageBuffer::default();
+        buffer.add(1, 3);
+        buffer.batches();
+
+        buffer.reported(1);
+
+        assert!(buffer.unreported().is_empty());
+        assert!(buffer.batches().is_empty());
+    }
+
+    #[test]
+    fn failed_batches_are_retried_with_the_same_key_and_quantity() {
+        let mut buffer = UsageBuffer::default();
+        buffer.add(1, 3);
+        let first = buffer.batches();
+
+        // the flush failed, usage keeps coming in
+        buffer.add(1, 5);
+        let retry = buffer.batches();
+
+        assert_eq!(retry, first);
+        assert_eq!(buffer.pending, HashMap::from([(1, 5)]));
+    }
+
+    #[test]
+    fn pending_and_in_flight_usage_are_merged() {
+        let mut buffer = UsageBuffer::default();
+        buffer.add(1, 3);
+        buffer.add(2, 4);
+        buffer.batches();
+        buffer.reported(2);
+
+        buffer.add(1, 5);
+        buffer.add(2, 6);
+        buffer.add(3, 7);
+
+        assert_eq!(buffer.unreported(), HashMap::from([(1, 8), (2, 6), (3, 7)]));
+    }
+
+    #[test]
+    fn rejected_batches_are_set_aside() {
+        let mut buffer = UsageBuffer::default();
+        buffer.add(1, 3);
+        buffer.batches();
+        buffer.add(1, 5);
+
+        buffer.rejected(1);
+        let next = buffer.batches();
+
+        assert_eq!(buffer.rejected, HashMap::from([(1, 3)]));
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].1.quantity, 5);
+        assert_eq!(buffer.unreported(), HashMap::from([(1, 5)]));
+    }
+
+    #[test]
+    fn error_bodies_are_not_reported() {
+        let body = serde_json::json!({
+            "jsonapi": { "version": "1.0" },
+            "errors": [{
+                "status": "429",
+                "title": "Too Many Requests",
+                "detail": "You have exceeded the rate limit."
+            }]
+        });
+        let response: Response<UsageRecordResponse> = serde_json::from_value(body).unwrap();
+
+        assert!(matches!(
+            Outcome::of(Ok(response)),
+            Outcome::Failed(NetworkError::MissingData)
+        ));
+    }
+
+    #[test]
+    fn client_errors_are_rejected_except_rate_limits_and_timeouts() {
+        let outcome = |status| Outcome::of(Err(NetworkError::StatusCodeError(status)));
+
+        assert!(matches!(
+            outcome(StatusCode::UNPROCESSABLE_ENTITY),
+            Outcome::Rejected(_)
+        ));
+        assert!(matches!(
+            outcome(StatusCode::NOT_FOUND),
+            Outcome::Rejected(_)
+        ));
+        assert!(matches!(
+            outcome(StatusCode::TOO_MANY_REQUESTS),
+            Outcome::Failed(_)
+        ));
+        assert!(matches!(
+            outcome(StatusCode::REQUEST_TIMEOUT),
+            Outcome::Failed(_)
+        ));
+        assert!(matches!(
+            outcome(StatusCode::BAD_GATEWAY),
+            Outcome::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn usage_held_back_by_a_retry_gets_its_own_batch_once_reported() {
+        let mut buffer = UsageBuffer::default();
+        buffer.add(1, 3);
+        let first = buffer.batches();
+        buffer.add(1, 5);
+        buffer.batches();
+
+        buffer.reported(1);
+        let next = buffer.batches();
+
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].1.quantity, 5);
+        assert_ne!(next[0].1.idempotency_key, first[0].1.idempotency_key);
+        assert!(buffer.pending.is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::utils::Response;
+use crate::utils::{Response, Timestamp};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UserResponse {
@@ -11,9 +11,9 @@ pub struct UserResponse {
     pub has_custom_avatar: bool,
 
     #[serde(rename = "createdAt")]
-    pub created_at: String,
+    pub created_at: Timestamp,
     #[serde(rename = "updatedAt")]
-    pub updated_at: String,
+    pub updated_at: Timestamp,
 }
 
 pub struct User {
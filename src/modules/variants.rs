@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VariantResponse {
@@ -26,10 +26,18 @@ pub struct VariantResponse {
     pub license_length_unit: Option<String>,
     pub is_license_length_unlimited: Option<bool>,
     pub sort: Option<i64>,
-    pub status: Option<String>,
+    pub status: Option<VariantStatus>,
     pub status_formatted: Option<String>,
-    pub created_at: String,
-    pub updated_at: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+string_enum! {
+    pub enum VariantStatus {
+        Pending => "pending",
+        Draft => "draft",
+        Published => "published",
+    }
 }
 
 pub struct VariantFilters {
@@ -4,10 +4,12 @@ pub use crate::orders::OrderResponse;
 pub use crate::subscriptions::SubscriptionResponse;
 pub use crate::license_keys::LicenseKeyResponse;
 
-use serde::{Deserialize, Serialize};
-use serde_json::json;
+use std::collections::HashMap;
 
-use crate::utils::{Response, ResponseData, VecResponse};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::utils::{Response, ResponseData, Timestamp, VecResponse};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WebhookOrderEvent<T> {
@@ -30,9 +32,21 @@ pub struct WebhookLicenseEvent<T> {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WebhookEventMeta<T> {
     pub event_name: String,
+    /// The custom data passed to the checkout. Use the struct given to
+    /// `CheckoutBuilder::custom`, wrapped in an `Option` if some checkouts have none
     pub custom_data: T,
 }
 
+impl WebhookEventMeta<Value> {
+    /// Deserialize the untyped custom data into the type it was created with
+    ///
+    /// # Returns
+    /// - `Result<C, serde_json::Error>` object
+    pub fn custom_data_as<C: DeserializeOwned>(&self) -> Result<C, serde_json::Error> {
+        serde_json::from_value(self.custom_data.clone())
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WebhookOrderData {
     pub r#type: String,
@@ -42,15 +56,24 @@ pub struct WebhookOrderData {
     pub links: WebhookLinks,
 }
 
+/// The `data` of a `subscription_*` webhook, a JSON:API subscription resource
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WebhookSubscriptionData {
     pub r#type: String,
-    pub id: i64,
+    /// The ID of the subscription, sent as a string
+    pub id: String,
     pub attributes: SubscriptionResponse,
-    pub relationships: WebhookRelationships,
+    /// The related resources by name, e.g. `customer`, `order`, `subscription-items`
+    #[serde(default)]
+    pub relationships: HashMap<String, WebhookRelationship>,
     pub links: WebhookLinks,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookRelationship {
+    pub links: RelationshipLinks,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WebhookLicenseData {
     pub r#type: String,
@@ -94,9 +117,9 @@ pub struct WebhookResponse {
     pub store_id: i64,
     pub url: String,
     pub events: Vec<String>,
-    pub last_sent_at: Option<String>,
-    pub created_at: String,
-    pub updated_at: String,
+    pub last_sent_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
     pub test_mode: bool,
 }
 
@@ -1,7 +1,11 @@
-use serde::{Deserialize, Serialize};
+pub use crate::errors::ValidationError;
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
 use super::Data;
+use crate::utils::Timestamp;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 
@@ -12,11 +16,12 @@ pub struct CheckoutResponse {
     pub product_options: ProductOptions,
     pub checkout_options: CheckoutOptions,
     pub checkout_data: CheckoutData,
-    // can either be false or Preview
-    pub preview: Value,
-    pub expires_at: Value,
-    pub created_at: String,
-    pub updated_at: String,
+    /// `None` unless the checkout was created with `preview: true`
+    #[serde(default, deserialize_with = "deserialize_preview")]
+    pub preview: Option<Preview>,
+    pub expires_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
     pub test_mode: bool,
     pub url: String,
 }
@@ -52,17 +57,30 @@ pub struct CheckoutOptions {
 pub struct CheckoutData {
     pub email: String,
     pub name: String,
-    pub billing_address: Vec<BillingAddress>,
+    #[serde(default, deserialize_with = "deserialize_billing_address")]
+    pub billing_address: Option<BillingAddress>,
     pub tax_number: String,
     pub discount_code: String,
-    pub custom: Vec<Value>,
+    pub custom: Value,
     pub variant_quantities: Vec<Value>,
 }
 
+impl CheckoutData {
+    /// Deserialize the custom data into the type it was created with
+    ///
+    /// # Returns
+    /// - `Result<C, serde_json::Error>` object
+    pub fn custom_as<C: DeserializeOwned>(&self) -> Result<C, serde_json::Error> {
+        serde_json::from_value(self.custom.clone())
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 
 pub struct BillingAddress {
+    #[serde(default)]
     pub country: String,
+    #[serde(default)]
     pub zip: String,
 }
 
@@ -70,7 +88,7 @@ pub struct BillingAddress {
 
 pub struct Preview {
     pub currency: String,
-    pub currency_rate: i64,
+    pub currency_rate: f64,
     pub subtotal: i64,
     pub discount_total: i64,
     pub tax: i64,
@@ -85,6 +103,33 @@ pub struct Preview {
     pub total_formatted: String,
 }
 
+/// The API returns `false` instead of a preview
+fn deserialize_preview<'de, D>(deserializer: D) -> Result<Option<Preview>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null | Value::Bool(false) => Ok(None),
+        value => serde_json::from_value(value)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// The API returns an empty array instead of an empty billing address
+fn deserialize_billing_address<'de, D>(deserializer: D) -> Result<Option<BillingAddress>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        Value::Array(values) if values.is_empty() => Ok(None),
+        value => serde_json::from_value(value)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateCheckoutRelationShip {
     r#type: String,
@@ -115,6 +160,9 @@ pub struct CreateCheckoutAttributes {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<bool>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub test_mode: Option<bool>,
 }
@@ -173,11 +221,33 @@ pub struct CreateCheckoutCheckoutData {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub discount_code: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub custom: Option<Vec<Value>>,
+    pub custom: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub variant_quantities: Option<Vec<i64>>,
 }
 
+impl CreateCheckoutCheckoutData {
+    /// Set the custom data from any type serializing to a JSON object
+    ///
+    /// The same type can be used to read the data back from
+    /// `WebhookEventMeta<T>.custom_data`
+    ///
+    /// # Returns
+    /// - `Result<(), ValidationError>` object
+    pub fn set_custom<C: Serialize>(&mut self, custom: &C) -> Result<(), ValidationError> {
+        match serde_json::to_value(custom) {
+            Ok(custom @ Value::Object(_)) => {
+                self.custom = Some(custom);
+                Ok(())
+            }
+            _ => Err(ValidationError::new(
+                "custom",
+                "must serialize to a JSON object",
+            )),
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateCheckoutBillingAddress {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -197,3 +267,464 @@ pub struct CreateCheckoutRelationShipData {
     pub r#type: String,
     pub id: String,
 }
+
+/// Builds a `CreateCheckout`, validating it before it is sent
+///
+/// # Example
+/// ```
+/// use chrono::{Duration, Utc};
+/// use lemonsqueezy::checkout::CheckoutBuilder;
+///
+/// let checkout = CheckoutBuilder::new(1, 2)
+///     .email("john@example.com")
+///     .discount_code("LAUNCH")
+///     .dark(true)
+///     .expires_at(Utc::now() + Duration::days(1))
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(checkout.relationships.unwrap().variant.data.id, "2");
+/// assert!(CheckoutBuilder::new(1, 2).custom_price(-1).build().is_err());
+/// ```
+///
+/// Custom data round-trips into the webhook meta with the same struct:
+/// ```
+/// use lemonsqueezy::checkout::CheckoutBuilder;
+/// use lemonsqueezy::webhook::WebhookEventMeta;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Metadata {
+///     user_id: String,
+///     team_id: i64,
+/// }
+///
+/// let metadata = Metadata { user_id: "u_123".to_string(), team_id: 7 };
+/// let checkout = CheckoutBuilder::new(1, 2).custom(&metadata).build().unwrap();
+/// let custom = checkout.attributes.checkout_data.unwrap().custom.unwrap();
+///
+/// let payload = serde_json::json!({ "event_name": "order_created", "custom_data": custom });
+/// let meta: WebhookEventMeta<Metadata> = serde_json::from_value(payload).unwrap();
+/// assert_eq!(meta.custom_data, metadata);
+///
+/// assert!(CheckoutBuilder::new(1, 2).custom(&vec![1, 2]).build().is_err());
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct CheckoutBuilder {
+    store_id: i64,
+    variant_id: i64,
+    custom_price: Option<i64>,
+    product_options: CreateCheckoutProductOptions,
+    checkout_options: CreateCheckoutCheckoutOptions,
+    checkout_data: CreateCheckoutCheckoutData,
+    expires_at: Option<DateTime<Utc>>,
+    preview: Option<bool>,
+    test_mode: Option<bool>,
+    custom_error: Option<ValidationError>,
+}
+
+impl CheckoutBuilder {
+    pub fn new(store_id: i64, variant_id: i64) -> Self {
+        Self {
+            store_id,
+            variant_id,
+            ..Default::default()
+        }
+    }
+
+    /// Prefill the email of the customer
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.checkout_data.email = Some(email.into());
+        self
+    }
+
+    /// Prefill the name of the customer
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.checkout_data.name = Some(name.into());
+        self
+    }
+
+    /// Prefill the billing address of the customer
+    pub fn billing_address(mut self, country: impl Into<String>, zip: impl Into<String>) -> Self {
+        self.checkout_data.billing_address = Some(BillingAddress {
+            country: country.into(),
+            zip: zip.into(),
+        });
+        self
+    }
+
+    /// Prefill the tax number of the customer
+    pub fn tax_number(mut self, tax_number: impl Into<String>) -> Self {
+        self.checkout_data.tax_number = Some(tax_number.into());
+        self
+    }
+
+    /// Prefill a discount code
+    pub fn discount_code(mut self, discount_code: impl Into<String>) -> Self {
+        self.checkout_data.discount_code = Some(discount_code.into());
+        self
+    }
+
+    /// Attach custom data, passed back in the `custom_data` of the webhook events
+    pub fn custom<C: Serialize>(mut self, custom: &C) -> Self {
+        self.custom_error = self.checkout_data.set_custom(custom).err();
+        self
+    }
+
+    /// Override the price of the variant, in cents
+    pub fn custom_price(mut self, custom_price: i64) -> Self {
+        self.custom_price = Some(custom_price);
+        self
+    }
+
+    /// Restrict the variants that can be picked in the checkout
+    pub fn enabled_variants(mut self, variant_ids: Vec<i64>) -> Self {
+        self.product_options.enabled_variants = Some(variant_ids);
+        self
+    }
+
+    /// Use the dark theme
+    pub fn dark(mut self, dark: bool) -> Self {
+        self.checkout_options.dark = Some(dark);
+        self
+    }
+
+    /// Show the checkout as an overlay
+    pub fn embed(mut self, embed: bool) -> Self {
+        self.checkout_options.embed = Some(embed);
+        self
+    }
+
+    /// The date after which the checkout can no longer be used
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// The URL the customer is redirected to after the purchase
+    pub fn redirect_url(mut self, redirect_url: impl Into<String>) -> Self {
+        self.product_options.redirect_url = Some(redirect_url.into());
+        self
+    }
+
+    /// Return a preview of the totals with the checkout
+    pub fn preview(mut self, preview: bool) -> Self {
+        self.preview = Some(preview);
+        self
+    }
+
+    /// Create the checkout in test mode
+    pub fn test_mode(mut self, test_mode: bool) -> Self {
+        self.test_mode = Some(test_mode);
+        self
+    }
+
+    /// Set the product options that have no dedicated setter
+    ///
+    /// The options are merged into the ones already set: a field set here overrides the current
+    /// value, e.g. from `redirect_url`, a field left `None` keeps it. The order of the calls only
+    /// matters for the fields set twice, the last call wins.
+    pub fn product_options(mut self, product_options: CreateCheckoutProductOptions) -> Self {
+        let current = self.product_options;
+
+        self.product_options = CreateCheckoutProductOptions {
+            name: product_options.name.or(current.name),
+            description: product_options.description.or(current.description),
+            media: product_options.media.or(current.media),
+            redirect_url: product_options.redirect_url.or(current.redirect_url),
+            receipt_button_text: product_options
+                .receipt_button_text
+                .or(current.receipt_button_text),
+            receipt_link_url: product_options
+                .receipt_link_url
+                .or(current.receipt_link_url),
+            receipt_thank_you_note: product_options
+                .receipt_thank_you_note
+                .or(current.receipt_thank_you_note),
+            enabled_variants: product_options
+                .enabled_variants
+                .or(current.enabled_variants),
+        };
+        self
+    }
+
+    /// Set the checkout options that have no dedicated setter
+    ///
+    /// The options are merged into the ones already set: a field set here overrides the current
+    /// value, e.g. from `dark` or `embed`, a field left `None` keeps it.
+    pub fn checkout_options(mut self, checkout_options: CreateCheckoutCheckoutOptions) -> Self {
+        let current = self.checkout_options;
+
+        self.checkout_options = CreateCheckoutCheckoutOptions {
+            embed: checkout_options.embed.or(current.embed),
+            media: checkout_options.media.or(current.media),
+            logo: checkout_options.logo.or(current.logo),
+            desc: checkout_options.desc.or(current.desc),
+            discount: checkout_options.discount.or(current.discount),
+            dark: checkout_options.dark.or(current.dark),
+            subscription_preview: checkout_options
+                .subscription_preview
+                .or(current.subscription_preview),
+            button_color: checkout_options.button_color.or(current.button_color),
+        };
+        self
+    }
+
+    /// Validate the checkout and build the request
+    ///
+    /// # Returns
+    /// - `Result<CreateCheckout, ValidationError>` object
+    pub fn build(self) -> Result<CreateCheckout, ValidationError> {
+        if let Some(error) = self.custom_error {
+            return Err(error);
+        }
+
+        if let Some(custom_price) = self.custom_price {
+            if custom_price < 0 {
+                return Err(ValidationError::new("custom_price", "must not be negative"));
+            }
+        }
+
+        if let Some(expires_at) = self.expires_at {
+            if expires_at <= Utc::now() {
+                return Err(ValidationError::new("expires_at", "must be in the future"));
+            }
+        }
+
+        if let Some(email) = &self.checkout_data.email {
+            if !email.contains('@') {
+                return Err(ValidationError::new("email", "must be an email address"));
+            }
+        }
+
+        if let Some(redirect_url) = &self.product_options.redirect_url {
+            match reqwest::Url::parse(redirect_url) {
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+                _ => {
+                    return Err(ValidationError::new(
+                        "redirect_url",
+                        "must be an absolute http(s) URL",
+                    ))
+                }
+            }
+        }
+
+        if let Some(enabled_variants) = &self.product_options.enabled_variants {
+            if enabled_variants.is_empty() {
+                return Err(ValidationError::new(
+                    "enabled_variants",
+                    "must not be empty",
+                ));
+            }
+        }
+
+        Ok(CreateCheckout {
+            r#type: "checkouts".to_string(),
+            attributes: CreateCheckoutAttributes {
+                custom_price: self.custom_price,
+                product_options: Some(self.product_options),
+                checkout_options: Some(self.checkout_options),
+                checkout_data: Some(self.checkout_data),
+                expires_at: self.expires_at.map(|expires_at| expires_at.to_rfc3339()),
+                preview: self.preview,
+                test_mode: self.test_mode,
+            },
+            relationships: Some(CreateCheckoutRelationships {
+                store: Data {
+                    data: CreateCheckoutRelationShipData {
+                        r#type: "stores".to_string(),
+                        id: self.store_id.to_string(),
+                    },
+                },
+                variant: Data {
+                    data: CreateCheckoutRelationShipData {
+                        r#type: "variants".to_string(),
+                        id: self.variant_id.to_string(),
+                    },
+                },
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The attributes of a checkout, as returned by the API
+    fn checkout(preview: Value, billing_address: Value) -> CheckoutResponse {
+        serde_json::from_value(serde_json::json!({
+            "store_id": 1,
+            "variant_id": 1,
+            "custom_price": null,
+            "product_options": {
+                "name": "",
+                "description": "",
+                "media": [],
+                "redirect_url": "",
+                "receipt_button_text": "",
+                "receipt_link_url": "",
+                "receipt_thank_you_note": "",
+                "enabled_variants": []
+            },
+            "checkout_options": {
+                "embed": false,
+                "media": true,
+                "logo": true,
+                "desc": true,
+                "discount": true,
+                "dark": false,
+                "subscription_preview": true,
+                "button_color": "#7047EB"
+            },
+            "checkout_data": {
+                "email": "",
+                "name": "",
+                "billing_address": billing_address,
+                "tax_number": "",
+                "discount_code": "",
+                "custom": [],
+                "variant_quantities": []
+            },
+            "preview": preview,
+            "expires_at": null,
+            "created_at": "2022-10-14T13:26:10.000000Z",
+            "updated_at": "2022-10-14T13:26:10.000000Z",
+            "test_mode": false,
+            "url": "https://my-store.lemonsqueezy.com/checkout/custom/44de2f11-5a5a-4e2b-8a9b-7a8c3a5c2b4a"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn deserializes_a_checkout_without_preview() {
+        let checkout = checkout(Value::Bool(false), serde_json::json!([]));
+
+        assert_eq!(checkout.preview, None);
+        assert_eq!(checkout.checkout_data.billing_address, None);
+    }
+
+    #[test]
+    fn deserializes_a_checkout_with_a_preview() {
+        let preview = serde_json::json!({
+            "currency": "USD",
+            "currency_rate": 1,
+            "subtotal": 1500,
+            "discount_total": 0,
+            "tax": 0,
+            "total": 1500,
+            "subtotal_usd": 1500,
+            "discount_total_usd": 0,
+            "tax_usd": 0,
+            "total_usd": 1500,
+            "subtotal_formatted": "$15.00",
+            "discount_total_formatted": "$0.00",
+            "tax_formatted": "$0.00",
+            "total_formatted": "$15.00"
+        });
+
+        let checkout = checkout(preview, Value::Null);
+        let preview = checkout.preview.unwrap();
+
+        assert_eq!(preview.currency, "USD");
+        assert_eq!(preview.total, 1500);
+        assert_eq!(preview.total_formatted, "$15.00");
+        assert_eq!(checkout.checkout_data.billing_address, None);
+    }
+
+    #[test]
+    fn deserializes_a_prefilled_billing_address() {
+        let checkout = checkout(
+            Value::Null,
+            serde_json::json!({ "country": "US", "zip": "10001" }),
+        );
+
+        assert_eq!(
+            checkout.checkout_data.billing_address,
+            Some(BillingAddress {
+                country: "US".to_string(),
+                zip: "10001".to_string(),
+            })
+        );
+        assert_eq!(checkout.preview, None);
+    }
+
+    #[test]
+    fn rejects_malformed_previews_and_billing_addresses() {
+        let response = serde_json::from_value::<CheckoutData>(serde_json::json!({
+            "email": "",
+            "name": "",
+            "billing_address": "US",
+            "tax_number": "",
+            "discount_code": "",
+            "custom": [],
+            "variant_quantities": []
+        }));
+
+        assert!(response.is_err());
+        assert!(deserialize_preview(serde_json::json!(true)).is_err());
+    }
+
+    #[test]
+    fn options_are_merged_whatever_the_order_of_the_calls() {
+        let product_options = CreateCheckoutProductOptions {
+            name: Some("Lemonade".to_string()),
+            ..Default::default()
+        };
+        let checkout_options = CreateCheckoutCheckoutOptions {
+            logo: Some(false),
+            ..Default::default()
+        };
+
+        let setters_first = CheckoutBuilder::new(1, 2)
+            .redirect_url("https://example.com/thanks")
+            .enabled_variants(vec![2, 3])
+            .dark(true)
+            .embed(true)
+            .product_options(product_options.clone())
+            .checkout_options(checkout_options.clone());
+        let setters_last = CheckoutBuilder::new(1, 2)
+            .product_options(product_options)
+            .checkout_options(checkout_options)
+            .redirect_url("https://example.com/thanks")
+            .enabled_variants(vec![2, 3])
+            .dark(true)
+            .embed(true);
+
+        assert_eq!(setters_first, setters_last);
+
+        let attributes = setters_first.build().unwrap().attributes;
+        let product_options = attributes.product_options.unwrap();
+        let checkout_options = attributes.checkout_options.unwrap();
+
+        assert_eq!(product_options.name.as_deref(), Some("Lemonade"));
+        assert_eq!(
+            product_options.redirect_url.as_deref(),
+            Some("https://example.com/thanks")
+        );
+        assert_eq!(product_options.enabled_variants, Some(vec![2, 3]));
+        assert_eq!(checkout_options.logo, Some(false));
+        assert_eq!(checkout_options.dark, Some(true));
+        assert_eq!(checkout_options.embed, Some(true));
+    }
+
+    #[test]
+    fn options_override_the_fields_they_set() {
+        let builder =
+            CheckoutBuilder::new(1, 2)
+                .dark(true)
+                .checkout_options(CreateCheckoutCheckoutOptions {
+                    dark: Some(false),
+                    ..Default::default()
+                });
+
+        let checkout_options = builder
+            .build()
+            .unwrap()
+            .attributes
+            .checkout_options
+            .unwrap();
+
+        assert_eq!(checkout_options.dark, Some(false));
+    }
+}
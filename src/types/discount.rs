@@ -1,11 +1,17 @@
+pub use crate::errors::ValidationError;
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::Data;
+use crate::discount::{CreateDiscount, CreateDiscountAttributes};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateDiscountRelationships {
     pub store: Data<CreateDiscountRelationshipsStore>,
-    pub variants: Data<Vec<CreateDiscountRelationshipsVariants>>,
+    /// Restricts the discount to these variants, store-wide if `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Data<Vec<CreateDiscountRelationshipsVariants>>>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -19,3 +25,246 @@ pub struct CreateDiscountRelationshipsVariants {
     pub r#type: String,
     pub id: String,
 }
+
+string_enum! {
+    pub enum AmountType {
+        /// `amount` is a percentage
+        Percent => "percent",
+        /// `amount` is a fixed amount, in cents
+        Fixed => "fixed",
+    }
+}
+
+string_enum! {
+    /// How long a discount applies to a subscription
+    pub enum DiscountDuration {
+        /// Only the first payment
+        Once => "once",
+        /// The payments of the first `duration_in_months` months
+        Repeating => "repeating",
+        /// Every payment
+        Forever => "forever",
+    }
+}
+
+/// Builds a `CreateDiscount`, validating it before it is sent
+///
+/// # Example
+/// ```
+/// use lemonsqueezy::discount::{AmountType, DiscountBuilder, DiscountDuration};
+///
+/// let discount = DiscountBuilder::new(1)
+///     .name("Launch")
+///     .code("LAUNCH20")
+///     .amount(20, AmountType::Percent)
+///     .duration(DiscountDuration::Repeating)
+///     .duration_in_months(3)
+///     .variants(vec![10, 11])
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(discount.attributes.is_limited_to_products, Some(true));
+/// assert_eq!(discount.relationships.variants.unwrap().data.len(), 2);
+///
+/// let discount = DiscountBuilder::new(1).name("Launch").code("LAUNCH20");
+/// assert!(discount.clone().amount(120, AmountType::Percent).build().is_err());
+/// assert!(discount.clone().code("launch").amount(20, AmountType::Percent).build().is_err());
+/// assert!(discount.clone().amount(20, AmountType::from("bogus")).build().is_err());
+/// assert!(discount.amount(500, AmountType::Fixed).build().is_ok());
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct DiscountBuilder {
+    pub(crate) store_id: i64,
+    name: String,
+    code: String,
+    amount: i64,
+    amount_type: AmountType,
+    variants: Option<Vec<i64>>,
+    max_redemptions: Option<i64>,
+    starts_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    duration: Option<DiscountDuration>,
+    duration_in_months: Option<i64>,
+}
+
+impl DiscountBuilder {
+    pub fn new(store_id: i64) -> Self {
+        Self {
+            store_id,
+            ..Default::default()
+        }
+    }
+
+    /// The name of the discount, shown to the customer
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// The code of the discount, 3 to 256 uppercase letters and numbers
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.code = code.into();
+        self
+    }
+
+    /// The amount of the discount, a percentage or a fixed amount in cents
+    pub fn amount(mut self, amount: i64, amount_type: AmountType) -> Self {
+        self.amount = amount;
+        self.amount_type = amount_type;
+        self
+    }
+
+    /// Restrict the discount to these variants
+    pub fn variants(mut self, variant_ids: Vec<i64>) -> Self {
+        self.variants = Some(variant_ids);
+        self
+    }
+
+    /// Limit the number of times the discount can be redeemed
+    pub fn max_redemptions(mut self, max_redemptions: i64) -> Self {
+        self.max_redemptions = Some(max_redemptions);
+        self
+    }
+
+    /// The date from which the discount can be redeemed
+    pub fn starts_at(mut self, starts_at: DateTime<Utc>) -> Self {
+        self.starts_at = Some(starts_at);
+        self
+    }
+
+    /// The date after which the discount can no longer be redeemed
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// How long the discount applies to a subscription
+    pub fn duration(mut self, duration: DiscountDuration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// The number of months a `DiscountDuration::Repeating` discount applies for
+    pub fn duration_in_months(mut self, duration_in_months: i64) -> Self {
+        self.duration_in_months = Some(duration_in_months);
+        self
+    }
+
+    /// Validate the discount and build the request
+    ///
+    /// # Returns
+    /// - `Result<CreateDiscount, ValidationError>` object
+    pub fn build(self) -> Result<CreateDiscount, ValidationError> {
+        if self.name.trim().is_empty() {
+            return Err(ValidationError::new("name", "must not be empty"));
+        }
+
+        if !(3..=256).contains(&self.code.len())
+            || !self
+                .code
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        {
+            return Err(ValidationError::new(
+                "code",
+                "must be 3 to 256 uppercase letters and numbers",
+            ));
+        }
+
+        if self.amount <= 0 {
+            return Err(ValidationError::new("amount", "must be positive"));
+        }
+
+        if let AmountType::Unknown(amount_type) = &self.amount_type {
+            return Err(ValidationError::new(
+                "amount_type",
+                format!("unknown amount type {:?}", amount_type),
+            ));
+        }
+
+        if self.amount_type == AmountType::Percent && self.amount > 100 {
+            return Err(ValidationError::new(
+                "amount",
+                "must not exceed 100 percent",
+            ));
+        }
+
+        if self.max_redemptions.is_some_and(|max| max <= 0) {
+            return Err(ValidationError::new("max_redemptions", "must be positive"));
+        }
+
+        if let (Some(starts_at), Some(expires_at)) = (self.starts_at, self.expires_at) {
+            if expires_at <= starts_at {
+                return Err(ValidationError::new(
+                    "expires_at",
+                    "must be after starts_at",
+                ));
+            }
+        }
+
+        match (&self.duration, self.duration_in_months) {
+            (Some(DiscountDuration::Unknown(duration)), _) => {
+                return Err(ValidationError::new(
+                    "duration",
+                    format!("unknown duration {:?}", duration),
+                ))
+            }
+            (Some(DiscountDuration::Repeating), Some(months)) if months > 0 => {}
+            (Some(DiscountDuration::Repeating), _) => {
+                return Err(ValidationError::new(
+                    "duration_in_months",
+                    "must be positive for a repeating discount",
+                ))
+            }
+            (_, Some(_)) => {
+                return Err(ValidationError::new(
+                    "duration_in_months",
+                    "only applies to a repeating discount",
+                ))
+            }
+            _ => {}
+        }
+
+        if self
+            .variants
+            .as_ref()
+            .is_some_and(|variants| variants.is_empty())
+        {
+            return Err(ValidationError::new("variants", "must not be empty"));
+        }
+
+        Ok(CreateDiscount {
+            r#type: "discounts".to_string(),
+            attributes: CreateDiscountAttributes {
+                name: self.name,
+                code: self.code,
+                amount: self.amount,
+                amount_type: self.amount_type,
+                is_limited_to_products: self.variants.as_ref().map(|_| true),
+                is_limited_redemptions: self.max_redemptions.map(|_| true),
+                max_redemptions: self.max_redemptions,
+                starts_at: self.starts_at.map(|starts_at| starts_at.to_rfc3339()),
+                expires_at: self.expires_at.map(|expires_at| expires_at.to_rfc3339()),
+                duration: self.duration,
+                duration_in_months: self.duration_in_months,
+            },
+            relationships: CreateDiscountRelationships {
+                store: Data {
+                    data: CreateDiscountRelationshipsStore {
+                        r#type: "stores".to_string(),
+                        id: self.store_id.to_string(),
+                    },
+                },
+                variants: self.variants.map(|variants| Data {
+                    data: variants
+                        .into_iter()
+                        .map(|variant_id| CreateDiscountRelationshipsVariants {
+                            r#type: "variants".to_string(),
+                            id: variant_id.to_string(),
+                        })
+                        .collect(),
+                }),
+            },
+        })
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// Billing details printed on a generated invoice
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvoiceDetails {
+    pub name: String,
+    pub address: String,
+    pub city: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    pub zip_code: String,
+    /// ISO 3166-1 alpha-2 country code
+    pub country: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedInvoice {
+    pub urls: GeneratedInvoiceUrls,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedInvoiceUrls {
+    pub download_invoice: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefundAttributes {
+    /// The amount to refund in cents, a full refund if `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 pub mod checkout;
 pub mod discount;
+pub mod invoice;
 pub mod webhook;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
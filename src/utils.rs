@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
+
 pub const API_URL: &str = "https://api.lemonsqueezy.com";
 
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
@@ -66,4 +68,134 @@ impl<T> Response<T> {
     pub fn get_inner_response (&self) -> Option<&T> {
         self.data.as_ref().and_then(|d| Some (&d.attributes))
     }
-}
\ No newline at end of file
+
+    /// Retrieves a reference to the data of the response, including its `id`, if it exists.
+    pub fn get_data(&self) -> Option<&ResponseData<T>> {
+        self.data.as_ref()
+    }
+}
+
+impl<T> ResponseMeta<T> {
+    /// Retrieves a reference to the meta of the response.
+    pub fn get_meta(&self) -> &T {
+        &self.meta
+    }
+}
+
+impl<T> VecResponse<T> {
+    /// Retrieves a reference to the inner response data.
+    pub fn get_inner_response(&self) -> &T {
+        &self.data
+    }
+
+    /// Consumes the response and returns the inner response data.
+    pub fn into_inner_response(self) -> T {
+        self.data
+    }
+
+    /// Retrieves the URL of the next page, if the response is paginated and there is one.
+    pub fn next_page(&self) -> Option<&str> {
+        self.links.as_ref().and_then(|l| l.next.as_deref())
+    }
+}
+
+/// Parses a LemonSqueezy timestamp (ISO 8601, e.g. `2021-08-11T13:47:27.000000Z`) into a UTC `DateTime`.
+pub fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// A timestamp of a response, kept as the raw ISO 8601 string sent by the API
+///
+/// Read it as a UTC `DateTime` with `ToDateTime::to_datetime`, or as the raw string with `as_str`.
+///
+/// ### Example
+/// ```rust
+/// use lemonsqueezy::utils::{Timestamp, ToDateTime};
+///
+/// let renews_at: Timestamp = "2024-01-31T00:00:00.000000Z".parse().unwrap();
+/// let ends_at: Option<Timestamp> = None;
+///
+/// assert_eq!(renews_at.as_str(), "2024-01-31T00:00:00.000000Z");
+/// assert_eq!(renews_at.to_datetime().unwrap().timestamp(), 1706659200);
+/// assert_eq!(Timestamp::from(renews_at.to_datetime().unwrap()), renews_at);
+/// assert_eq!(ends_at.to_datetime(), None);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Timestamp(String);
+
+impl Timestamp {
+    /// The timestamp as sent by the API
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for Timestamp {
+    type Err = std::convert::Infallible;
+
+    fn from_str(timestamp: &str) -> Result<Self, Self::Err> {
+        Ok(Self(timestamp.to_string()))
+    }
+}
+
+impl From<String> for Timestamp {
+    fn from(timestamp: String) -> Self {
+        Self(timestamp)
+    }
+}
+
+impl From<&str> for Timestamp {
+    fn from(timestamp: &str) -> Self {
+        Self(timestamp.to_string())
+    }
+}
+
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(timestamp: DateTime<Utc>) -> Self {
+        Self(timestamp.to_rfc3339_opts(chrono::SecondsFormat::Micros, true))
+    }
+}
+
+impl From<Timestamp> for String {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+/// Reads a timestamp as a UTC `DateTime`, `None` if it is missing or malformed
+pub trait ToDateTime {
+    fn to_datetime(&self) -> Option<DateTime<Utc>>;
+}
+
+impl ToDateTime for Timestamp {
+    fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        parse_timestamp(&self.0)
+    }
+}
+
+impl ToDateTime for String {
+    fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        parse_timestamp(self)
+    }
+}
+
+impl ToDateTime for DateTime<Utc> {
+    fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        Some(*self)
+    }
+}
+
+impl<T: ToDateTime> ToDateTime for Option<T> {
+    fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        self.as_ref().and_then(ToDateTime::to_datetime)
+    }
+}